@@ -0,0 +1,74 @@
+//! Request authentication.
+//!
+//! Admin routes require `Authorization: Bearer <ADMIN_TOKEN>`. When
+//! `ADMIN_TOKEN` is not set the admin API is disabled and every admin request
+//! is refused.
+//!
+//! GitHub webhooks are authenticated by the HMAC-SHA256 of the body, keyed
+//! with `GITHUB_WEBHOOK_SECRET`, sent in the `X-Hub-Signature-256` header.
+
+use crate::api::state::AppState;
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use ring::hmac;
+
+/// Reject requests that do not carry the configured admin token.
+pub async fn require_admin(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Admin API disabled: ADMIN_TOKEN is not set".to_string(),
+        ));
+    };
+
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match provided {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+            Ok(next.run(request).await)
+        }
+        _ => {
+            metrics::counter!("admin_auth_failures_total").increment(1);
+            Err((
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid admin token".to_string(),
+            ))
+        }
+    }
+}
+
+/// Compare two byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Check a GitHub `X-Hub-Signature-256` header (`sha256=<hex>`) against `body`.
+pub fn verify_github_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix("sha256=").and_then(decode_hex) else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, body, &expected).is_ok()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -1,4 +1,5 @@
-use utoipa::OpenApi;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -7,25 +8,53 @@ use utoipa::OpenApi;
         crate::api::handlers::metrics_handler,
         crate::api::handlers::content_handler,
         crate::api::handlers::ticker_stats_handler,
-        crate::api::handlers::ticker_history_handler
+        crate::api::handlers::ticker_history_handler,
+        crate::api::handlers::index_handler,
+        crate::api::handlers::tokens_handler,
+        crate::api::handlers::token_exchanges_handler,
+        crate::api::handlers::coverage_handler,
+        crate::api::handlers::cache_keys_handler,
+        crate::api::handlers::cache_entry_handler,
+        crate::api::handlers::cache_invalidate_handler,
+        crate::api::handlers::github_webhook_handler
     ),
     components(
         schemas(
             crate::api::handlers::AggregateQuery,
             crate::api::handlers::HealthResponse,
             crate::api::handlers::HealthDependencies,
+            crate::infrastructure::mirror::MirrorStatus,
+            crate::infrastructure::breaker::CircuitStatus,
             crate::api::handlers::TickerStatsResponse,
             crate::api::handlers::TickerHistoryResponse,
             crate::api::handlers::ExchangeStats,
             crate::api::handlers::AggregateStats,
-            crate::api::handlers::OhlcvPoint
+            crate::api::handlers::OhlcvPoint,
+            crate::api::handlers::ExchangeSeries,
+            crate::api::handlers::IndexResponse,
+            crate::api::handlers::IndexPoint,
+            crate::api::handlers::TokensResponse,
+            crate::api::handlers::ExchangesResponse,
+            crate::api::handlers::CoverageResponse,
+            crate::api::handlers::YearCoverage,
+            crate::api::handlers::MonthCoverage,
+            crate::api::handlers::DayCoverage,
+            crate::api::handlers::CacheKeysResponse,
+            crate::api::handlers::CacheEntryResponse,
+            crate::api::handlers::InvalidateResponse,
+            crate::api::handlers::WebhookResponse
         )
     ),
     tags(
         (name = "system", description = "System endpoints for health checks and metrics"),
         (name = "content", description = "Content retrieval endpoints for accessing repository data"),
-        (name = "ticker", description = "Simplified ticker data endpoints for aggregated token statistics")
+        (name = "ticker", description = "Simplified ticker data endpoints for aggregated token statistics"),
+        (name = "index", description = "Composite cross-exchange reference price"),
+        (name = "catalog", description = "Discovery of tokens, exchanges and date coverage"),
+        (name = "admin", description = "Cache administration (requires the admin bearer token)"),
+        (name = "webhooks", description = "Notifications from GitHub about data repository changes")
     ),
+    modifiers(&AdminTokenAddon),
     info(
         title = "Kaspa Exchange Data API",
         version = "0.1.0",
@@ -38,3 +67,16 @@ use utoipa::OpenApi;
 )]
 pub struct ApiDoc;
 
+/// Registers the bearer token scheme used by the admin endpoints.
+struct AdminTokenAddon;
+
+impl Modify for AdminTokenAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
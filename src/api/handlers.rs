@@ -1,7 +1,9 @@
+use crate::application::cache::Cached;
 use crate::application::service::AggregateOptions;
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -10,10 +12,27 @@ use tracing::instrument;
 use validator::Validate;
 
 use crate::api::state::AppState;
+use crate::infrastructure::breaker::CircuitStatus;
+use crate::infrastructure::mirror::MirrorStatus;
 use utoipa::{IntoParams, ToSchema};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Header set on responses served from a cache entry past its soft TTL.
+const STALE_HEADER: &str = "x-cache-stale";
+
+impl<T: Serialize> IntoResponse for Cached<T> {
+    fn into_response(self) -> Response {
+        let mut response = Json(self.value).into_response();
+        if self.stale {
+            response
+                .headers_mut()
+                .insert(STALE_HEADER, HeaderValue::from_static("true"));
+        }
+        response
+    }
+}
+
 #[derive(Deserialize, IntoParams, ToSchema, Debug, Validate)]
 pub struct AggregateQuery {
     /// Enable aggregation mode to combine multiple files
@@ -47,6 +66,13 @@ pub struct HealthResponse {
     pub backend: String,
     pub config: String,
     pub dependencies: HealthDependencies,
+    /// Git mirror sync state (present when the mirror is enabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<Vec<MirrorStatus>>,
+    /// Upstream circuit breaker states (present when circuit breaking is enabled).
+    /// Open circuits do not make the service unhealthy: cached data is still served.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuits: Option<Vec<CircuitStatus>>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -73,6 +99,16 @@ pub async fn health_handler(
         Err(_) => "error",
     };
 
+    let mirror = match &state.mirror {
+        Some(mirror) => Some(mirror.status().await),
+        None => None,
+    };
+
+    let circuits = state
+        .circuit_breaker
+        .as_ref()
+        .map(|breaker| breaker.status());
+
     let overall_status = if redis_status == "healthy" {
         "ok"
     } else {
@@ -88,6 +124,8 @@ pub async fn health_handler(
         dependencies: HealthDependencies {
             redis: redis_status.to_string(),
         },
+        mirror,
+        circuits,
     };
 
     if overall_status == "ok" {
@@ -105,11 +143,8 @@ pub async fn health_handler(
         (status = 200, description = "Prometheus metrics", content_type = "text/plain")
     )
 )]
-pub async fn metrics_handler() -> impl IntoResponse {
-    let handle = metrics_exporter_prometheus::PrometheusBuilder::new()
-        .install_recorder()
-        .expect("Failed to install Prometheus recorder");
-    handle.render()
+pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    state.metrics.render()
 }
 
 #[utoipa::path(
@@ -140,7 +175,8 @@ pub async fn metrics_handler() -> impl IntoResponse {
         (status = 404, description = "Not Found - Resource does not exist",
             example = json!({"error": "Resource not found: github/KaspaDev/Kaspa-Exchange-Data/invalid/path"})
         ),
-        (status = 500, description = "Internal Server Error")
+        (status = 500, description = "Internal Server Error"),
+        (status = 503, description = "Upstream unavailable - circuit open or rate limit exhausted")
     )
 )]
 #[instrument(skip(state), fields(source = %source, owner = %owner, repo = %repo, path = %path, aggregate = ?query.aggregate))]
@@ -182,46 +218,83 @@ pub async fn content_handler(
     {
         Ok(data) => {
             // Success
-            Ok(Json(data).into_response())
+            Ok(data.into_response())
         }
         Err(e) => {
             // Map anyhow error to status code with context
-            let msg = e.to_string();
             let request_info = format!("{}/{}/{}/{}", source, owner, repo, path);
-
-            if msg.contains("Access Denied") {
-                Err((
-                    StatusCode::FORBIDDEN,
-                    format!("Access denied for repository: {}", request_info),
-                ))
-            } else if msg.contains("Not found") || msg.contains("404") {
-                Err((
-                    StatusCode::NOT_FOUND,
-                    format!("Resource not found: {}", request_info),
-                ))
-            } else if msg.contains("Too many items") {
-                Err((StatusCode::BAD_REQUEST, msg))
-            } else {
-                tracing::error!("Internal error for {}: {}", request_info, msg);
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Internal server error processing: {}", request_info),
-                ))
-            }
+            Err(service_error(
+                e,
+                &format!("Internal server error processing: {}", request_info),
+                |msg| {
+                    if msg.contains("Access Denied") {
+                        Some((
+                            StatusCode::FORBIDDEN,
+                            format!("Access denied for repository: {}", request_info),
+                        ))
+                    } else if msg.contains("Not found") || msg.contains("404") {
+                        Some((
+                            StatusCode::NOT_FOUND,
+                            format!("Resource not found: {}", request_info),
+                        ))
+                    } else if msg.contains("Too many items") || msg.contains("Invalid path") {
+                        Some((StatusCode::BAD_REQUEST, msg.to_string()))
+                    } else {
+                        None
+                    }
+                },
+            ))
         }
     }
 }
 
+/// Map a service error to a response.
+///
+/// Upstream outages (circuit breaker open, every GitHub credential rate
+/// limited) are 503 for every endpoint. Other errors are given to `classify`
+/// for the endpoint's own client errors, and are otherwise logged and
+/// answered with a 500 carrying `context`.
+fn service_error(
+    e: anyhow::Error,
+    context: &str,
+    classify: impl FnOnce(&str) -> Option<(StatusCode, String)>,
+) -> (StatusCode, String) {
+    let msg = e.to_string();
+    if msg.contains("Circuit open") || msg.contains("rate limit exhausted") {
+        return (StatusCode::SERVICE_UNAVAILABLE, msg);
+    }
+    if let Some(response) = classify(&msg) {
+        return response;
+    }
+    tracing::error!("{}: {}", context, msg);
+    (StatusCode::INTERNAL_SERVER_ERROR, context.to_string())
+}
+
 // Re-export ticker types for use in doc.rs
 pub use crate::application::ticker_service::{
-    AggregateStats, ExchangeStats, OhlcvPoint, TickerHistoryQuery, TickerHistoryResponse,
-    TickerStatsQuery, TickerStatsResponse,
+    max_history_span_days, parse_exchange_list, AggregateStats, DateWindow, ExchangeSeries,
+    ExchangeStats, HistoryGrouping, OhlcvPoint, TickerHistoryQuery, TickerHistoryResponse,
+    TickerStatsQuery, TickerStatsResponse, HISTORY_RESOLUTIONS, MAX_STATS_SPAN_DAYS,
 };
 
+/// Resolve the `quote` query parameter, defaulting to the reference quote.
+fn resolve_quote(state: &AppState, quote: Option<&str>) -> Result<String, (StatusCode, String)> {
+    let Some(quote) = quote else {
+        return Ok(state.quote_service.reference().to_string());
+    };
+    let quote = quote.trim().to_uppercase();
+    if state.quote_service.is_supported(&quote) {
+        Ok(quote)
+    } else {
+        Err((StatusCode::BAD_REQUEST, format!("Unsupported quote: {}", quote)))
+    }
+}
+
 /// Get current stats for a token across all exchanges.
 ///
 /// Returns aggregated statistics for the specified token across all
-/// supported exchanges, with optional lookback range.
+/// supported exchanges, with optional lookback range. Exchanges flagged as
+/// stale or outliers are excluded from `aggregate` unless `include_flagged=true`.
 #[utoipa::path(
     get,
     path = "/v1/ticker/{token}",
@@ -236,56 +309,72 @@ pub use crate::application::ticker_service::{
                 "token": "slow",
                 "timestamp": "2025-12-29T22:45:00Z",
                 "range": "today",
+                "from": "2025-12-29",
+                "to": "2025-12-29",
+                "quote": "USDT",
                 "exchanges": [
-                    {"exchange": "ascendex", "last": 0.000123, "high": 0.00013, "low": 0.000118, "volume_24h": 1234567.89, "change_pct": 2.5, "data_points": 1440}
+                    {"exchange": "ascendex", "last": 0.000123, "high": 0.00013, "low": 0.000118, "volume_24h": 1234567.89, "change_pct": 2.5, "data_points": 1440,
+                        "last_update": "2025-12-29T22:44:12+00:00", "stale": false, "outlier": false, "quote": "USDT", "normalized": true}
                 ],
-                "aggregate": {"avg_price": 0.0001235, "total_volume_24h": 2500000.0, "vwap": 0.0001233, "exchange_count": 2}
+                "aggregate": {"avg_price": 0.0001235, "total_volume_24h": 2500000.0, "vwap": 0.0001233, "exchange_count": 2, "excluded_count": 0}
             })
         ),
+        (status = 400, description = "Invalid range, from/to window, or quote"),
         (status = 404, description = "Token not found"),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Upstream unavailable - circuit open or rate limit exhausted")
     )
 )]
-#[instrument(skip(state), fields(token = %token, range = ?query.range))]
+#[instrument(skip(state), fields(token = %token, range = ?query.range, from = ?query.from, to = ?query.to, include_flagged = ?query.include_flagged, quote = ?query.quote))]
 pub async fn ticker_stats_handler(
     Path(token): Path<String>,
     Query(query): Query<TickerStatsQuery>,
     State(state): State<AppState>,
-) -> Result<Json<TickerStatsResponse>, (StatusCode, String)> {
-    let range = query.range.unwrap_or_else(|| "today".to_string());
+) -> Result<Cached<TickerStatsResponse>, (StatusCode, String)> {
+    let window = DateWindow::resolve(
+        query.range.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+        "today",
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
 
-    // Validate range
-    if !["today", "7d", "30d"].contains(&range.as_str()) {
+    if window.span_days() > MAX_STATS_SPAN_DAYS {
         return Err((
             StatusCode::BAD_REQUEST,
-            "Invalid range. Use: today, 7d, or 30d".to_string(),
+            format!(
+                "Window too large: {} days (max: {})",
+                window.span_days(),
+                MAX_STATS_SPAN_DAYS
+            ),
         ));
     }
 
     metrics::counter!("api_requests_total", "endpoint" => "ticker_stats", "token" => token.clone())
         .increment(1);
 
-    match state.ticker_service.get_ticker_stats(token.clone(), range).await {
-        Ok(response) => Ok(Json(response)),
-        Err(e) => {
-            let msg = e.to_string();
-            if msg.contains("No exchanges found") {
-                Err((StatusCode::NOT_FOUND, format!("Token not found: {}", token)))
-            } else {
-                tracing::error!("Ticker stats error for {}: {}", token, msg);
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to get stats for token: {}", token),
-                ))
-            }
-        }
+    let quote = resolve_quote(&state, query.quote.as_deref())?;
+    let include_flagged = query.include_flagged.unwrap_or(false);
+    match state
+        .ticker_service
+        .get_ticker_stats(token.clone(), window, include_flagged, quote)
+        .await
+    {
+        Ok(response) => Ok(response),
+        Err(e) => Err(service_error(
+            e,
+            &format!("Failed to get stats for token: {}", token),
+            |msg| token_not_found(msg, &token),
+        )),
     }
 }
 
 /// Get historical data for a token (for charting).
 ///
-/// Returns OHLCV data aggregated across exchanges for the specified
-/// token, suitable for charting applications.
+/// Returns OHLCV data for the specified token, suitable for charting
+/// applications. Candles are built per exchange; by default they are combined
+/// into one composite series, while `group_by=exchange` returns each
+/// exchange's series separately.
 #[utoipa::path(
     get,
     path = "/v1/ticker/{token}/history",
@@ -299,38 +388,63 @@ pub async fn ticker_stats_handler(
             example = json!({
                 "token": "kaspa",
                 "range": "7d",
+                "from": "2025-12-22",
+                "to": "2025-12-29",
                 "resolution": "1h",
+                "quote": "USDT",
+                "volume_method": "rolling_24h_delta",
+                "group_by": "composite",
+                "exchanges": ["ascendex", "xeggex"],
                 "data": [
-                    {"timestamp": 1735500000, "open": 0.04512, "high": 0.04561, "low": 0.04381, "close": 0.04505, "volume": 60853.37}
+                    {"timestamp": 1735500000, "open": 0.04512, "high": 0.04561, "low": 0.04381, "close": 0.04505, "volume": 60853.37, "sources": 2}
                 ]
             })
         ),
-        (status = 404, description = "Token not found"),
-        (status = 500, description = "Internal server error")
+        (status = 400, description = "Invalid range, resolution, from/to window, exchange, group_by, or quote"),
+        (status = 404, description = "Token or exchange not found"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Upstream unavailable - circuit open or rate limit exhausted")
     )
 )]
-#[instrument(skip(state), fields(token = %token, range = ?query.range, resolution = ?query.resolution))]
+#[instrument(skip(state), fields(token = %token, range = ?query.range, from = ?query.from, to = ?query.to, resolution = ?query.resolution, exchange = ?query.exchange, group_by = ?query.group_by, quote = ?query.quote))]
 pub async fn ticker_history_handler(
     Path(token): Path<String>,
     Query(query): Query<TickerHistoryQuery>,
     State(state): State<AppState>,
-) -> Result<Json<TickerHistoryResponse>, (StatusCode, String)> {
-    let range = query.range.unwrap_or_else(|| "7d".to_string());
+) -> Result<Cached<TickerHistoryResponse>, (StatusCode, String)> {
     let resolution = query.resolution.unwrap_or_else(|| "1h".to_string());
 
-    // Validate range
-    if !["today", "7d", "30d"].contains(&range.as_str()) {
+    // Validate resolution
+    if !HISTORY_RESOLUTIONS.contains(&resolution.as_str()) {
         return Err((
             StatusCode::BAD_REQUEST,
-            "Invalid range. Use: today, 7d, or 30d".to_string(),
+            "Invalid resolution. Use: 1m, 5m, 1h, or 1d".to_string(),
         ));
     }
 
-    // Validate resolution
-    if !["1m", "5m", "1h", "1d"].contains(&resolution.as_str()) {
+    let window = DateWindow::resolve(
+        query.range.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+        "7d",
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let exchanges =
+        parse_exchange_list(query.exchange.as_deref()).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let grouping =
+        HistoryGrouping::parse(query.group_by.as_deref()).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let quote = resolve_quote(&state, query.quote.as_deref())?;
+
+    let max_span = max_history_span_days(&resolution);
+    if window.span_days() > max_span {
         return Err((
             StatusCode::BAD_REQUEST,
-            "Invalid resolution. Use: 1m, 5m, 1h, or 1d".to_string(),
+            format!(
+                "Window too large for {} resolution: {} days (max: {})",
+                resolution,
+                window.span_days(),
+                max_span
+            ),
         ));
     }
 
@@ -339,21 +453,526 @@ pub async fn ticker_history_handler(
 
     match state
         .ticker_service
-        .get_ticker_history(token.clone(), range, resolution)
+        .get_ticker_history(token.clone(), window, resolution, exchanges, grouping, quote)
+        .await
+    {
+        Ok(response) => Ok(response),
+        Err(e) => Err(service_error(
+            e,
+            &format!("Failed to get history for token: {}", token),
+            |msg| {
+                if msg.contains("Exchange not found") {
+                    Some((StatusCode::NOT_FOUND, msg.to_string()))
+                } else {
+                    token_not_found(msg, &token)
+                }
+            },
+        )),
+    }
+}
+
+// Re-export index types for use in doc.rs
+pub use crate::application::index_service::{IndexPoint, IndexQuery, IndexResponse};
+use crate::domain::pricing::Weighting;
+
+/// Get the composite cross-exchange price index for a token.
+///
+/// Combines each exchange's latest price at the end of every bucket into one
+/// reference price, excluding stale venues and rejecting outliers.
+#[utoipa::path(
+    get,
+    path = "/v1/index/{token}",
+    params(
+        ("token" = String, Path, description = "Token symbol (e.g., kaspa, slow, nacho)", example = "kaspa"),
+        IndexQuery
+    ),
+    tag = "index",
+    responses(
+        (status = 200, description = "Index computed successfully", body = IndexResponse,
+            example = json!({
+                "token": "kaspa",
+                "range": "today",
+                "from": "2025-12-30",
+                "to": "2025-12-30",
+                "resolution": "1h",
+                "weighting": "volume",
+                "quote": "USDT",
+                "stale_after_secs": 3600,
+                "outlier_threshold_pct": 10.0,
+                "exchanges": ["ascendex", "xeggex"],
+                "data": [
+                    {"timestamp": 1767052800, "price": 0.04507, "exchange_count": 2, "stale": 0, "outliers": 0}
+                ]
+            })
+        ),
+        (status = 400, description = "Invalid range, resolution, weighting, or from/to window"),
+        (status = 404, description = "Token not found"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Upstream unavailable - circuit open or rate limit exhausted")
+    )
+)]
+#[instrument(skip(state), fields(token = %token, range = ?query.range, from = ?query.from, to = ?query.to, resolution = ?query.resolution, weighting = ?query.weighting))]
+pub async fn index_handler(
+    Path(token): Path<String>,
+    Query(query): Query<IndexQuery>,
+    State(state): State<AppState>,
+) -> Result<Cached<IndexResponse>, (StatusCode, String)> {
+    let resolution = query.resolution.unwrap_or_else(|| "1h".to_string());
+
+    // Validate resolution
+    if !["5m", "1h", "1d"].contains(&resolution.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Invalid resolution. Use: 5m, 1h, or 1d".to_string(),
+        ));
+    }
+
+    let weighting = match query.weighting.as_deref() {
+        Some(w) => Weighting::parse(w).map_err(|e| (StatusCode::BAD_REQUEST, e))?,
+        None => state.index_service.default_weighting(),
+    };
+
+    let window = DateWindow::resolve(
+        query.range.as_deref(),
+        query.from.as_deref(),
+        query.to.as_deref(),
+        "today",
+    )
+    .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let max_span = max_history_span_days(&resolution);
+    if window.span_days() > max_span {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Window too large for {} resolution: {} days (max: {})",
+                resolution,
+                window.span_days(),
+                max_span
+            ),
+        ));
+    }
+
+    metrics::counter!("api_requests_total", "endpoint" => "index", "token" => token.clone())
+        .increment(1);
+
+    match state
+        .index_service
+        .get_index(token.clone(), window, resolution, weighting)
         .await
     {
-        Ok(response) => Ok(Json(response)),
+        Ok(response) => Ok(response),
+        Err(e) => Err(service_error(
+            e,
+            &format!("Failed to compute index for token: {}", token),
+            |msg| token_not_found(msg, &token),
+        )),
+    }
+}
+
+/// 404 for services reporting that `token` has no exchanges.
+fn token_not_found(msg: &str, token: &str) -> Option<(StatusCode, String)> {
+    msg.contains("No exchanges found")
+        .then(|| (StatusCode::NOT_FOUND, format!("Token not found: {}", token)))
+}
+
+// Re-export catalog types for use in doc.rs
+pub use crate::application::catalog_service::{
+    is_valid_name, CoverageResponse, DayCoverage, ExchangesResponse, MonthCoverage,
+    TokensResponse, YearCoverage,
+};
+
+/// Map a catalog error to a response, treating missing directories as 404.
+fn catalog_error(e: anyhow::Error, what: String) -> (StatusCode, String) {
+    service_error(e, &format!("Failed to list {}", what), |msg| {
+        (msg.contains("Not found") || msg.contains("404"))
+            .then(|| (StatusCode::NOT_FOUND, format!("{} not found", what)))
+    })
+}
+
+/// List all tokens with data.
+#[utoipa::path(
+    get,
+    path = "/v1/tokens",
+    tag = "catalog",
+    responses(
+        (status = 200, description = "Tokens listed successfully", body = TokensResponse,
+            example = json!({"tokens": ["kaspa", "nacho", "slow"], "count": 3})
+        ),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Upstream unavailable - circuit open or rate limit exhausted")
+    )
+)]
+#[instrument(skip(state))]
+pub async fn tokens_handler(
+    State(state): State<AppState>,
+) -> Result<Json<TokensResponse>, (StatusCode, String)> {
+    metrics::counter!("api_requests_total", "endpoint" => "tokens").increment(1);
+
+    state
+        .catalog_service
+        .list_tokens()
+        .await
+        .map(Json)
+        .map_err(|e| catalog_error(e, "Tokens".to_string()))
+}
+
+/// List the exchanges carrying a token.
+#[utoipa::path(
+    get,
+    path = "/v1/tokens/{token}/exchanges",
+    params(
+        ("token" = String, Path, description = "Token symbol (e.g., kaspa, slow, nacho)", example = "kaspa")
+    ),
+    tag = "catalog",
+    responses(
+        (status = 200, description = "Exchanges listed successfully", body = ExchangesResponse,
+            example = json!({"token": "slow", "exchanges": ["ascendex", "fameex"], "count": 2})
+        ),
+        (status = 400, description = "Invalid token name"),
+        (status = 404, description = "Token not found"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Upstream unavailable - circuit open or rate limit exhausted")
+    )
+)]
+#[instrument(skip(state), fields(token = %token))]
+pub async fn token_exchanges_handler(
+    Path(token): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<ExchangesResponse>, (StatusCode, String)> {
+    if !is_valid_name(&token) {
+        return Err((StatusCode::BAD_REQUEST, format!("Invalid token: {}", token)));
+    }
+
+    metrics::counter!("api_requests_total", "endpoint" => "token_exchanges", "token" => token.clone())
+        .increment(1);
+
+    state
+        .catalog_service
+        .list_exchanges(&token)
+        .await
+        .map(Json)
+        .map_err(|e| catalog_error(e, format!("Token {}", token)))
+}
+
+/// Describe the dates and file kinds available for a token on an exchange.
+#[utoipa::path(
+    get,
+    path = "/v1/tokens/{token}/exchanges/{exchange}/coverage",
+    params(
+        ("token" = String, Path, description = "Token symbol (e.g., kaspa, slow, nacho)", example = "kaspa"),
+        ("exchange" = String, Path, description = "Exchange identifier", example = "ascendex")
+    ),
+    tag = "catalog",
+    responses(
+        (status = 200, description = "Coverage retrieved successfully", body = CoverageResponse,
+            example = json!({
+                "token": "kaspa",
+                "exchange": "ascendex",
+                "first_date": "2025-12-28",
+                "last_date": "2025-12-29",
+                "day_count": 2,
+                "file_kinds": ["raw", "summary"],
+                "years": [
+                    {"year": "2025", "months": [
+                        {"month": "12", "days": [
+                            {"date": "2025-12-28", "kinds": ["raw", "summary"]},
+                            {"date": "2025-12-29", "kinds": ["raw", "summary"]}
+                        ]}
+                    ]}
+                ]
+            })
+        ),
+        (status = 400, description = "Invalid token or exchange name"),
+        (status = 404, description = "Token or exchange not found"),
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Upstream unavailable - circuit open or rate limit exhausted")
+    )
+)]
+#[instrument(skip(state), fields(token = %token, exchange = %exchange))]
+pub async fn coverage_handler(
+    Path((token, exchange)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> Result<Json<CoverageResponse>, (StatusCode, String)> {
+    if !is_valid_name(&token) || !is_valid_name(&exchange) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid token or exchange: {}/{}", token, exchange),
+        ));
+    }
+
+    metrics::counter!("api_requests_total", "endpoint" => "coverage", "token" => token.clone())
+        .increment(1);
+
+    state
+        .catalog_service
+        .get_coverage(&token, &exchange)
+        .await
+        .map(Json)
+        .map_err(|e| catalog_error(e, format!("Exchange {} for {}", exchange, token)))
+}
+
+// Re-export cache admin types for use in doc.rs
+pub use crate::application::cache_admin::{
+    CacheEntryResponse, CacheKeysResponse, InvalidateResponse,
+};
+
+/// Default and maximum number of keys listed by the cache admin endpoint.
+const DEFAULT_KEY_LIMIT: usize = 100;
+const MAX_KEY_LIMIT: usize = 1000;
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct CacheKeysQuery {
+    /// Key pattern, where `*` matches any run of characters
+    #[param(example = "v1:ticker:kaspa:*")]
+    pub pattern: String,
+
+    /// Maximum number of keys to list (1-1000)
+    #[param(default = 100, minimum = 1, maximum = 1000, example = 100)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct CacheEntryQuery {
+    /// Exact cache key
+    #[param(example = "v1:ticker:kaspa:stats:today:USDT:filtered")]
+    pub key: String,
+}
+
+/// Map a cache admin error to a 500 response.
+fn cache_admin_error(e: anyhow::Error) -> (StatusCode, String) {
+    tracing::error!("Cache admin error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Cache operation failed".to_string(),
+    )
+}
+
+/// List cached keys matching a pattern.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/cache/keys",
+    params(CacheKeysQuery),
+    tag = "admin",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Keys listed successfully", body = CacheKeysResponse,
+            example = json!({
+                "pattern": "v1:ticker:kaspa:*",
+                "keys": ["v1:ticker:kaspa:stats:7d:USDT:filtered", "v1:ticker:kaspa:stats:today:USDT:filtered"],
+                "count": 2,
+                "truncated": false
+            })
+        ),
+        (status = 400, description = "Empty pattern"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 403, description = "Admin API disabled"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[instrument(skip(state), fields(pattern = %query.pattern, limit = ?query.limit))]
+pub async fn cache_keys_handler(
+    Query(query): Query<CacheKeysQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<CacheKeysResponse>, (StatusCode, String)> {
+    if query.pattern.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Pattern must not be empty".to_string(),
+        ));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_KEY_LIMIT)
+        .clamp(1, MAX_KEY_LIMIT);
+
+    state
+        .cache_admin
+        .list_keys(&query.pattern, limit)
+        .await
+        .map(Json)
+        .map_err(cache_admin_error)
+}
+
+/// Show a cached entry.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/cache/entry",
+    params(CacheEntryQuery),
+    tag = "admin",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Entry found", body = CacheEntryResponse,
+            example = json!({
+                "key": "v1:ticker:kaspa:stats:today:USDT:filtered",
+                "fresh": true,
+                "fresh_until": "2026-01-02T12:05:00+00:00",
+                "size": 2048,
+                "value": {"token": "kaspa"}
+            })
+        ),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 403, description = "Admin API disabled"),
+        (status = 404, description = "Key not cached"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[instrument(skip(state), fields(key = %query.key))]
+pub async fn cache_entry_handler(
+    Query(query): Query<CacheEntryQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<CacheEntryResponse>, (StatusCode, String)> {
+    match state.cache_admin.inspect(&query.key).await {
+        Ok(Some(entry)) => Ok(Json(entry)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Key not cached: {}", query.key),
+        )),
+        Err(e) => Err(cache_admin_error(e)),
+    }
+}
+
+/// Invalidate every cached key matching a pattern.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/cache/keys",
+    params(CacheKeysQuery),
+    tag = "admin",
+    security(("admin_token" = [])),
+    responses(
+        (status = 200, description = "Keys invalidated", body = InvalidateResponse,
+            example = json!({"pattern": "v1:gh:*", "deleted": 42})
+        ),
+        (status = 400, description = "Empty pattern"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 403, description = "Admin API disabled"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[instrument(skip(state), fields(pattern = %query.pattern))]
+pub async fn cache_invalidate_handler(
+    Query(query): Query<CacheKeysQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<InvalidateResponse>, (StatusCode, String)> {
+    if query.pattern.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Pattern must not be empty".to_string(),
+        ));
+    }
+
+    let deleted = state
+        .cache_admin
+        .invalidate(&query.pattern)
+        .await
+        .map_err(cache_admin_error)?;
+    Ok(Json(InvalidateResponse {
+        pattern: query.pattern,
+        deleted,
+    }))
+}
+
+// Re-export webhook types for use in doc.rs
+use crate::api::auth::verify_github_signature;
+pub use crate::application::webhook::{PushEvent, WebhookResponse};
+
+/// Receive GitHub webhook deliveries for the data repository.
+///
+/// A `push` to the default branch of a whitelisted `github` repository
+/// invalidates the cache entries derived from the changed files. When the git
+/// mirror is enabled it is asked to sync instead, and invalidation follows once
+/// the new commit is checked out, so the old files are not cached again.
+#[utoipa::path(
+    post,
+    path = "/v1/webhooks/github",
+    tag = "webhooks",
+    params(
+        ("X-GitHub-Event" = String, Header, description = "GitHub event name", example = "push"),
+        ("X-Hub-Signature-256" = String, Header, description = "HMAC-SHA256 of the body keyed with the webhook secret", example = "sha256=...")
+    ),
+    request_body(content = Object, description = "GitHub webhook payload", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Delivery processed", body = WebhookResponse,
+            example = json!({"event": "push", "status": "invalidated", "deleted": 12})
+        ),
+        (status = 400, description = "Malformed payload"),
+        (status = 401, description = "Missing or invalid signature"),
+        (status = 403, description = "Webhooks disabled"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+#[instrument(skip(state, headers, body))]
+pub async fn github_webhook_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<WebhookResponse>, (StatusCode, String)> {
+    let Some(secret) = state.webhook_secret.as_deref() else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Webhooks disabled: GITHUB_WEBHOOK_SECRET is not set".to_string(),
+        ));
+    };
+    let signature = headers
+        .get("x-hub-signature-256")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !verify_github_signature(secret, &body, signature) {
+        metrics::counter!("webhook_events_total", "event" => "unknown", "result" => "unauthorized")
+            .increment(1);
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            "Missing or invalid signature".to_string(),
+        ));
+    }
+
+    let event = headers
+        .get("x-github-event")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    let respond = |status: &str, deleted: usize| {
+        metrics::counter!("webhook_events_total", "event" => event.clone(), "result" => status.to_string())
+            .increment(1);
+        Json(WebhookResponse {
+            event: event.clone(),
+            status: status.to_string(),
+            deleted,
+        })
+    };
+    if event != "push" {
+        return Ok(respond("ignored", 0));
+    }
+
+    let push: PushEvent = serde_json::from_slice(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid push payload: {}", e),
+        )
+    })?;
+    let repos = state.webhook_service.affected_repos(&push);
+    if repos.is_empty() {
+        return Ok(respond("ignored", 0));
+    }
+    tracing::info!(
+        "Push to {} ({}) received",
+        push.repository.full_name,
+        push.after
+    );
+
+    if let Some(mirror) = &state.mirror {
+        mirror.request_sync();
+        return Ok(respond("sync_requested", 0));
+    }
+
+    match state.webhook_service.invalidate(&push, &repos).await {
+        Ok(deleted) => Ok(respond("invalidated", deleted)),
         Err(e) => {
-            let msg = e.to_string();
-            if msg.contains("No exchanges found") {
-                Err((StatusCode::NOT_FOUND, format!("Token not found: {}", token)))
-            } else {
-                tracing::error!("Ticker history error for {}: {}", token, msg);
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    format!("Failed to get history for token: {}", token),
-                ))
-            }
+            tracing::error!("Webhook invalidation failed: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Cache invalidation failed".to_string(),
+            ))
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod auth;
 pub mod doc;
 pub mod handlers;
 pub mod routes;
@@ -1,7 +1,8 @@
+use crate::api::auth::require_admin;
 use crate::api::doc::ApiDoc;
-use crate::api::handlers::{content_handler, health_handler, metrics_handler, ticker_stats_handler, ticker_history_handler, dashboard_handler};
+use crate::api::handlers::{content_handler, health_handler, metrics_handler, ticker_stats_handler, ticker_history_handler, index_handler, tokens_handler, token_exchanges_handler, coverage_handler, dashboard_handler, cache_keys_handler, cache_entry_handler, cache_invalidate_handler, github_webhook_handler};
 use crate::api::state::AppState;
-use axum::{routing::get, Router};
+use axum::{middleware, routing::{get, post}, Router};
 use std::time::Duration;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
@@ -36,6 +37,15 @@ pub fn create_router(state: AppState, allowed_origins: String) -> Router {
         ))
         .layer(cors);
 
+    // Admin endpoints, behind the admin token
+    let admin = Router::new()
+        .route(
+            "/v1/admin/cache/keys",
+            get(cache_keys_handler).delete(cache_invalidate_handler),
+        )
+        .route("/v1/admin/cache/entry", get(cache_entry_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_admin));
+
     Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         // Dashboard (development)
@@ -51,8 +61,20 @@ pub fn create_router(state: AppState, allowed_origins: String) -> Router {
         // NEW: Ticker convenience endpoints
         .route("/v1/ticker/{token}", get(ticker_stats_handler))
         .route("/v1/ticker/{token}/history", get(ticker_history_handler))
+        // Catalog discovery endpoints
+        .route("/v1/tokens", get(tokens_handler))
+        .route("/v1/tokens/{token}/exchanges", get(token_exchanges_handler))
+        .route(
+            "/v1/tokens/{token}/exchanges/{exchange}/coverage",
+            get(coverage_handler),
+        )
+        // GitHub push notifications
+        .route("/v1/webhooks/github", post(github_webhook_handler))
+        // Composite cross-exchange price index
+        .route("/v1/index/{token}", get(index_handler))
         // Legacy route for backwards compatibility (can be removed later)
         .route("/api/{source}/{owner}/{repo}/{*path}", get(content_handler))
+        .merge(admin)
         .layer(middleware)
         .with_state(state)
 }
@@ -1,8 +1,28 @@
-use crate::application::{ContentService, TickerService};
+use crate::application::{
+    CacheAdminService, CatalogService, ContentService, IndexService, QuoteService, TickerService,
+    WebhookService,
+};
+use crate::infrastructure::{CircuitBreaker, GitMirrorRepository};
+use metrics_exporter_prometheus::PrometheusHandle;
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub content_service: Arc<ContentService>,
     pub ticker_service: Arc<TickerService>,
+    pub index_service: Arc<IndexService>,
+    pub quote_service: Arc<QuoteService>,
+    pub catalog_service: Arc<CatalogService>,
+    pub cache_admin: Arc<CacheAdminService>,
+    pub webhook_service: Arc<WebhookService>,
+    /// Prometheus recorder installed at startup, rendered by `/metrics`
+    pub metrics: PrometheusHandle,
+    /// Local git mirror, when enabled in `config.yaml`
+    pub mirror: Option<Arc<GitMirrorRepository>>,
+    /// Circuit breakers guarding GitHub, when enabled in `config.yaml`
+    pub circuit_breaker: Option<Arc<CircuitBreaker>>,
+    /// Bearer token required by the admin endpoints (`ADMIN_TOKEN`); admin is disabled when unset
+    pub admin_token: Option<String>,
+    /// Secret GitHub webhooks are signed with (`GITHUB_WEBHOOK_SECRET`); webhooks are refused when unset
+    pub webhook_secret: Option<String>,
 }
@@ -0,0 +1,512 @@
+//! Stale-while-revalidate response caching.
+//!
+//! Responses are stored with a soft and a hard TTL (see
+//! [`crate::domain::CacheEntry`]). Within the soft TTL an entry is served as a
+//! normal hit. Between the soft and hard TTL it is served immediately, marked
+//! stale, while a single background refresh per key recomputes it; if upstream
+//! fails, the stale value keeps being served instead of an error. Only when no
+//! entry exists does a request wait for upstream.
+//!
+//! Misses are coalesced so that only one computation per key is in flight:
+//! concurrent requests in one process share a single future, and across
+//! replicas a Redis lock keyed by the cache key elects one computing instance
+//! while the others poll the cache for its result. A waiter that sees the lock
+//! released without a result (or waits longer than `lock_ttl_secs`) computes
+//! the value itself.
+//!
+//! Data for past days never changes, so keys can get their own TTLs through
+//! `ttl_rules`: the first rule whose pattern matches the key (and, for
+//! `historical` rules, whose newest `YYYY-MM-DD` date is older than the last
+//! `mutable_days` days) sets the soft and hard TTL. Other keys, including
+//! today's and yesterday's files, use the default TTLs.
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! cache:
+//!   soft_ttl_secs: 300
+//!   hard_ttl_secs: 3600
+//!   lock_ttl_secs: 30
+//!   mutable_days: 2
+//!   ttl_rules:
+//!     - pattern: "v1:gh:*"
+//!       historical: true
+//!       soft_ttl_secs: 2592000
+//!       hard_ttl_secs: 2592000
+//! ```
+
+use crate::domain::{glob_match, CacheRepository};
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use futures::future::{BoxFuture, Shared};
+use futures::FutureExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// How often a replica waiting on another replica's computation polls the cache.
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A computation shared by concurrent requests for one key: the serialized
+/// value, or the error message.
+type Flight = Shared<BoxFuture<'static, Result<String, String>>>;
+
+/// Cache settings loaded from the `cache` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CacheConfig {
+    /// Seconds a response is served as fresh (default: 300)
+    #[serde(default = "default_soft_ttl_secs")]
+    pub soft_ttl_secs: u64,
+    /// Seconds a stale response is kept for serving while upstream is refreshed
+    /// or unavailable (default: 3600)
+    #[serde(default = "default_hard_ttl_secs")]
+    pub hard_ttl_secs: u64,
+    /// Seconds a replica holds the lock for computing a key, and the longest
+    /// other replicas wait for its result (default: 30)
+    #[serde(default = "default_lock_ttl_secs")]
+    pub lock_ttl_secs: u64,
+    /// Number of most recent days, including today, whose data may still
+    /// change; older dates count as historical (default: 2)
+    #[serde(default = "default_mutable_days")]
+    pub mutable_days: u32,
+    /// TTL overrides by key pattern; the first matching rule applies
+    /// (default: 30 days for historical `v1:gh:*`, `v1:ticker:*` and `v1:index:*` keys)
+    #[serde(default = "default_ttl_rules")]
+    pub ttl_rules: Vec<TtlRule>,
+}
+
+/// TTLs for the cache keys matching a pattern.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TtlRule {
+    /// Glob over the cache key, where `*` matches any run of characters
+    pub pattern: String,
+    /// Only match keys whose newest date is historical (default: false)
+    #[serde(default)]
+    pub historical: bool,
+    /// Seconds a matching response is served as fresh
+    pub soft_ttl_secs: u64,
+    /// Seconds a matching response is kept (default: `soft_ttl_secs`)
+    #[serde(default)]
+    pub hard_ttl_secs: Option<u64>,
+}
+
+impl TtlRule {
+    fn matches(&self, key: &str, historical_before: NaiveDate) -> bool {
+        glob_match(&self.pattern, key)
+            && (!self.historical || newest_date(key).is_some_and(|d| d < historical_before))
+    }
+}
+
+fn default_soft_ttl_secs() -> u64 {
+    300
+}
+fn default_hard_ttl_secs() -> u64 {
+    3600
+}
+fn default_lock_ttl_secs() -> u64 {
+    30
+}
+fn default_mutable_days() -> u32 {
+    2
+}
+fn default_ttl_rules() -> Vec<TtlRule> {
+    const HISTORICAL_TTL_SECS: u64 = 30 * 24 * 3600;
+    ["v1:gh:*", "v1:ticker:*", "v1:index:*"]
+        .into_iter()
+        .map(|pattern| TtlRule {
+            pattern: pattern.to_string(),
+            historical: true,
+            soft_ttl_secs: HISTORICAL_TTL_SECS,
+            hard_ttl_secs: None,
+        })
+        .collect()
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            soft_ttl_secs: default_soft_ttl_secs(),
+            hard_ttl_secs: default_hard_ttl_secs(),
+            lock_ttl_secs: default_lock_ttl_secs(),
+            mutable_days: default_mutable_days(),
+            ttl_rules: default_ttl_rules(),
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Soft and hard TTL, in seconds, for `key`.
+    pub fn ttls_for(&self, key: &str) -> (u64, u64) {
+        let today = Utc::now().date_naive();
+        let historical_before = today - ChronoDuration::days(i64::from(self.mutable_days) - 1);
+        match self
+            .ttl_rules
+            .iter()
+            .find(|rule| rule.matches(key, historical_before))
+        {
+            Some(rule) => (
+                rule.soft_ttl_secs,
+                rule.hard_ttl_secs.unwrap_or(rule.soft_ttl_secs),
+            ),
+            None => (self.soft_ttl_secs, self.hard_ttl_secs),
+        }
+    }
+}
+
+/// Newest `YYYY-MM-DD` date appearing anywhere in `key`.
+pub(crate) fn newest_date(key: &str) -> Option<NaiveDate> {
+    let bytes = key.as_bytes();
+    (0..bytes.len().saturating_sub(9))
+        .filter(|&i| {
+            bytes[i..i + 10].iter().enumerate().all(|(j, b)| match j {
+                4 | 7 => *b == b'-',
+                _ => b.is_ascii_digit(),
+            })
+        })
+        .filter_map(|i| NaiveDate::parse_from_str(&key[i..i + 10], "%Y-%m-%d").ok())
+        .max()
+}
+
+/// A response together with whether it was served past its soft TTL.
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    pub value: T,
+    /// The value is older than the soft TTL
+    pub stale: bool,
+}
+
+/// Stale-while-revalidate cache shared by the services.
+#[derive(Clone)]
+pub struct ResponseCache {
+    cache_repo: Arc<dyn CacheRepository>,
+    settings: CacheConfig,
+    /// Keys with a background refresh in flight
+    refreshing: Arc<Mutex<HashSet<String>>>,
+    /// Computations of missing keys in flight in this process
+    in_flight: Arc<Mutex<HashMap<String, Flight>>>,
+    /// Identifies this replica as a lock owner
+    instance_id: String,
+}
+
+impl ResponseCache {
+    pub fn new(cache_repo: Arc<dyn CacheRepository>, settings: CacheConfig) -> Self {
+        Self {
+            cache_repo,
+            settings,
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            instance_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Serve `key` from the cache, computing it with `compute` when needed.
+    ///
+    /// `compute` only runs on a miss (awaited, coalesced with concurrent misses
+    /// for the same key) or for a stale entry (spawned in the background, at
+    /// most once per key at a time).
+    pub async fn get_or_compute<T, F>(&self, key: String, compute: F) -> anyhow::Result<Cached<T>>
+    where
+        T: Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        if let Ok(Some(entry)) = self.cache_repo.get_entry(&key).await {
+            if let Ok(value) = serde_json::from_str::<T>(&entry.value) {
+                if entry.is_fresh() {
+                    info!("Cache HIT: {}", key);
+                    metrics::counter!("cache_operations_total", "operation" => "hit").increment(1);
+                    return Ok(Cached {
+                        value,
+                        stale: false,
+                    });
+                }
+
+                info!("Cache STALE: {}", key);
+                metrics::counter!("cache_operations_total", "operation" => "stale").increment(1);
+                self.spawn_refresh(key, compute);
+                return Ok(Cached { value, stale: true });
+            }
+        }
+
+        metrics::counter!("cache_operations_total", "operation" => "miss").increment(1);
+        let flight = self.join_flight(key, compute);
+        let json = flight.await.map_err(|e| anyhow::anyhow!(e))?;
+        Ok(Cached {
+            value: serde_json::from_str(&json)?,
+            stale: false,
+        })
+    }
+
+    /// Store a freshly computed value under `key`.
+    pub async fn store<T: Serialize>(&self, key: &str, value: &T) {
+        if let Ok(json) = serde_json::to_string(value) {
+            self.store_json(key, &json).await;
+        }
+    }
+
+    async fn store_json(&self, key: &str, json: &str) {
+        let (soft_ttl, hard_ttl) = self.settings.ttls_for(key);
+        let _ = self
+            .cache_repo
+            .set_entry(key, json, soft_ttl, hard_ttl)
+            .await;
+    }
+
+    /// The in-process computation of `key`, started with `compute` unless one
+    /// is already in flight.
+    fn join_flight<T, F>(&self, key: String, compute: F) -> Flight
+    where
+        T: Serialize + Send + 'static,
+        F: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        let mut flights = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(flight) = flights.get(&key) {
+            metrics::counter!("cache_coalesced_total", "scope" => "local").increment(1);
+            return flight.clone();
+        }
+
+        let cache = self.clone();
+        let flight_key = key.clone();
+        let flight = async move {
+            let result = cache.compute_exclusive(&flight_key, compute).await;
+            cache
+                .in_flight
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&flight_key);
+            result
+        }
+        .boxed()
+        .shared();
+        flights.insert(key, flight.clone());
+        flight
+    }
+
+    /// Compute and store `key` while holding its distributed lock.
+    ///
+    /// When another replica holds the lock, waits for the value it stores
+    /// instead; if the lock is released without a value or the wait times out,
+    /// computes the value here.
+    async fn compute_exclusive<T, F>(&self, key: &str, compute: F) -> Result<String, String>
+    where
+        T: Serialize,
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        let lock_key = format!("v1:lock:{}", key);
+        let lock_ttl = self.settings.lock_ttl_secs.max(1);
+        let mut locked = self.take_lock(&lock_key, lock_ttl).await;
+
+        if !locked {
+            metrics::counter!("cache_coalesced_total", "scope" => "remote").increment(1);
+            let deadline = Instant::now() + Duration::from_secs(lock_ttl);
+            while Instant::now() < deadline {
+                tokio::time::sleep(LOCK_POLL_INTERVAL).await;
+                if let Ok(Some(entry)) = self.cache_repo.get_entry(key).await {
+                    if entry.is_fresh() {
+                        return Ok(entry.value);
+                    }
+                }
+                if self.take_lock(&lock_key, lock_ttl).await {
+                    locked = true;
+                    break;
+                }
+            }
+        }
+
+        let result = match compute.await {
+            Ok(value) => match serde_json::to_string(&value) {
+                Ok(json) => {
+                    self.store_json(key, &json).await;
+                    Ok(json)
+                }
+                Err(e) => Err(e.to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        };
+        if locked {
+            let _ = self.cache_repo.unlock(&lock_key, &self.instance_id).await;
+        }
+        result
+    }
+
+    /// Try to take a distributed lock; a failing cache counts as taken.
+    async fn take_lock(&self, lock_key: &str, ttl: u64) -> bool {
+        self.cache_repo
+            .try_lock(lock_key, &self.instance_id, ttl)
+            .await
+            .unwrap_or(true)
+    }
+
+    /// Recompute a stale entry in the background unless a refresh is already running.
+    fn spawn_refresh<T, F>(&self, key: String, compute: F)
+    where
+        T: Serialize + Send + Sync + 'static,
+        F: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        let started = match self.refreshing.lock() {
+            Ok(mut refreshing) => refreshing.insert(key.clone()),
+            Err(_) => false,
+        };
+        if !started {
+            return;
+        }
+
+        let cache = self.clone();
+        tokio::spawn(async move {
+            // Another replica holding the lock is already refreshing this key
+            let lock_key = format!("v1:lock:{}", key);
+            if cache
+                .take_lock(&lock_key, cache.settings.lock_ttl_secs.max(1))
+                .await
+            {
+                match compute.await {
+                    Ok(value) => {
+                        cache.store(&key, &value).await;
+                        metrics::counter!("cache_refreshes_total", "result" => "ok").increment(1);
+                    }
+                    Err(e) => {
+                        warn!("Background refresh failed for {}: {}", key, e);
+                        metrics::counter!("cache_refreshes_total", "result" => "error")
+                            .increment(1);
+                    }
+                }
+                let _ = cache.cache_repo.unlock(&lock_key, &cache.instance_id).await;
+            }
+            if let Ok(mut refreshing) = cache.refreshing.lock() {
+                refreshing.remove(&key);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::MemoryCacheRepository;
+    use async_trait::async_trait;
+
+    /// Cache whose reads and writes work but whose lock commands always fail,
+    /// like Redis when the pool cannot connect.
+    struct FailingLockRepository {
+        inner: MemoryCacheRepository,
+    }
+
+    #[async_trait]
+    impl CacheRepository for FailingLockRepository {
+        async fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+            self.inner.get(key).await
+        }
+        async fn set(&self, key: &str, value: &str, ttl_seconds: u64) -> anyhow::Result<()> {
+            self.inner.set(key, value, ttl_seconds).await
+        }
+        async fn try_lock(&self, _: &str, _: &str, _: u64) -> anyhow::Result<bool> {
+            anyhow::bail!("lock unavailable")
+        }
+        async fn unlock(&self, _: &str, _: &str) -> anyhow::Result<()> {
+            anyhow::bail!("lock unavailable")
+        }
+        async fn delete(&self, key: &str) -> anyhow::Result<bool> {
+            self.inner.delete(key).await
+        }
+        async fn scan(&self, pattern: &str, limit: usize) -> anyhow::Result<Vec<String>> {
+            self.inner.scan(pattern, limit).await
+        }
+    }
+
+    fn failing_lock_cache() -> (ResponseCache, Arc<FailingLockRepository>) {
+        let repo = Arc::new(FailingLockRepository {
+            inner: MemoryCacheRepository::new(1024 * 1024),
+        });
+        let settings = CacheConfig {
+            lock_ttl_secs: 30,
+            ..CacheConfig::default()
+        };
+        (ResponseCache::new(repo.clone(), settings), repo)
+    }
+
+    #[tokio::test]
+    async fn miss_computes_at_once_when_lock_fails() {
+        let (cache, _) = failing_lock_cache();
+        let result = tokio::time::timeout(
+            Duration::from_secs(2),
+            cache.get_or_compute("v1:test:miss".to_string(), async { Ok(42u32) }),
+        )
+        .await
+        .expect("miss waited for the lock instead of computing")
+        .unwrap();
+        assert_eq!(result.value, 42);
+        assert!(!result.stale);
+    }
+
+    #[tokio::test]
+    async fn stale_entry_refreshes_when_lock_fails() {
+        let (cache, repo) = failing_lock_cache();
+        // Stale (soft TTL elapsed) but not expired
+        repo.set_entry("v1:test:stale", "1", 0, 60).await.unwrap();
+
+        let result = cache
+            .get_or_compute("v1:test:stale".to_string(), async { Ok(2u32) })
+            .await
+            .unwrap();
+        assert_eq!(result.value, 1);
+        assert!(result.stale);
+
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if let Some(entry) = repo.get_entry("v1:test:stale").await.unwrap() {
+                if entry.value == "2" {
+                    return;
+                }
+            }
+        }
+        panic!("stale entry was not refreshed");
+    }
+
+    #[test]
+    fn newest_date_picks_latest_valid_date() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(
+            newest_date("v1:ticker:btc:rates:2025-12-26:2025-12-29"),
+            Some(date("2025-12-29"))
+        );
+        assert_eq!(
+            newest_date("v1:gh:github:o:r:data/kas/x/2025/12/2025-12-28-raw.json"),
+            Some(date("2025-12-28"))
+        );
+        // At the very end of the key
+        assert_eq!(newest_date("2024-02-29"), Some(date("2024-02-29")));
+        // Impossible dates and look-alikes are ignored
+        assert_eq!(newest_date("v1:x:2025-02-30:2025-1-01"), None);
+        assert_eq!(newest_date("v1:ticker:kaspa:stats:7d"), None);
+        assert_eq!(newest_date("2025-12-2"), None);
+        assert_eq!(newest_date(""), None);
+    }
+
+    #[test]
+    fn historical_rule_needs_an_old_date() {
+        let settings = CacheConfig::default();
+        let long = (30 * 24 * 3600, 30 * 24 * 3600);
+        let short = (settings.soft_ttl_secs, settings.hard_ttl_secs);
+
+        assert_eq!(
+            settings.ttls_for("v1:ticker:btc:rates:2020-01-01:2020-01-10"),
+            long
+        );
+        let today = Utc::now().date_naive();
+        assert_eq!(
+            settings.ttls_for(&format!("v1:ticker:btc:rates:2020-01-01:{}", today)),
+            short
+        );
+        assert_eq!(settings.ttls_for("v1:ticker:kaspa:stats:7d"), short);
+        assert_eq!(
+            settings.ttls_for("v1:index:kaspa:2020-01-01..2020-01-10:1h"),
+            long
+        );
+        assert_eq!(settings.ttls_for("v1:catalog:2020-01-01"), short);
+    }
+}
@@ -0,0 +1,249 @@
+//! Cache administration.
+//!
+//! `CacheAdminService` backs the admin endpoints that list, inspect and
+//! invalidate cached keys, and purges the entries affected when the data
+//! repository moves to a new commit.
+//!
+//! Invalidation after a commit is targeted when the changed paths are known:
+//! the content entries of each changed file and of the directories above it,
+//! and the ticker, index and catalog entries of the tokens it belongs to.
+//! Derived entries whose key names a date range ending before the earliest
+//! changed day are kept, so long-lived historical entries survive routine
+//! commits that only touch today's files.
+
+use crate::application::cache::newest_date;
+use crate::domain::{CacheEntry, CacheRepository, RepoConfig};
+use chrono::{DateTime, NaiveDate};
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tracing::info;
+use utoipa::ToSchema;
+
+/// Most keys removed by a single invalidation.
+const MAX_INVALIDATE_KEYS: usize = 100_000;
+
+/// Concurrent deletes while invalidating.
+const DELETE_CONCURRENCY: usize = 32;
+
+/// Response structure for the cache key listing endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CacheKeysResponse {
+    /// Pattern the keys were matched against
+    pub pattern: String,
+    /// Matching keys, in alphabetical order
+    pub keys: Vec<String>,
+    /// Number of keys returned
+    pub count: usize,
+    /// More keys matched than `limit`
+    pub truncated: bool,
+}
+
+/// Response structure for the cache entry endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CacheEntryResponse {
+    /// Cache key
+    pub key: String,
+    /// Whether the entry is within its soft TTL
+    pub fresh: bool,
+    /// Time the entry goes stale (ISO 8601), if stored with a soft TTL
+    pub fresh_until: Option<String>,
+    /// Size of the cached value in bytes
+    pub size: usize,
+    /// Cached value, parsed as JSON when possible
+    #[schema(value_type = Object)]
+    pub value: serde_json::Value,
+}
+
+/// Response structure for the cache invalidation endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct InvalidateResponse {
+    /// Pattern of the removed keys
+    pub pattern: String,
+    /// Number of keys removed
+    pub deleted: usize,
+}
+
+/// Service listing, inspecting and invalidating cache entries.
+#[derive(Clone)]
+pub struct CacheAdminService {
+    cache_repo: Arc<dyn CacheRepository>,
+}
+
+impl CacheAdminService {
+    pub fn new(cache_repo: Arc<dyn CacheRepository>) -> Self {
+        Self { cache_repo }
+    }
+
+    /// List up to `limit` keys matching `pattern`.
+    pub async fn list_keys(
+        &self,
+        pattern: &str,
+        limit: usize,
+    ) -> anyhow::Result<CacheKeysResponse> {
+        let mut keys = self.cache_repo.scan(pattern, limit + 1).await?;
+        keys.sort();
+        let truncated = keys.len() > limit;
+        keys.truncate(limit);
+        Ok(CacheKeysResponse {
+            pattern: pattern.to_string(),
+            count: keys.len(),
+            keys,
+            truncated,
+        })
+    }
+
+    /// Describe the entry stored under `key`, if any.
+    pub async fn inspect(&self, key: &str) -> anyhow::Result<Option<CacheEntryResponse>> {
+        let Some(raw) = self.cache_repo.get(key).await? else {
+            return Ok(None);
+        };
+        let (entry, enveloped) = match serde_json::from_str::<CacheEntry>(&raw) {
+            Ok(entry) => (entry, true),
+            Err(_) => (
+                CacheEntry {
+                    fresh_until: 0,
+                    value: raw,
+                },
+                false,
+            ),
+        };
+        Ok(Some(CacheEntryResponse {
+            key: key.to_string(),
+            fresh: entry.is_fresh(),
+            fresh_until: enveloped
+                .then(|| DateTime::from_timestamp(entry.fresh_until, 0))
+                .flatten()
+                .map(|t| t.to_rfc3339()),
+            size: entry.value.len(),
+            value: serde_json::from_str(&entry.value)
+                .unwrap_or(serde_json::Value::String(entry.value)),
+        }))
+    }
+
+    /// Remove every key matching `pattern`, returning how many were removed.
+    pub async fn invalidate(&self, pattern: &str) -> anyhow::Result<usize> {
+        let deleted = self.delete_matching(pattern, |_| true).await?;
+        info!("Invalidated {} cache keys matching {}", deleted, pattern);
+        metrics::counter!("cache_invalidated_keys_total").increment(deleted as u64);
+        Ok(deleted)
+    }
+
+    /// Remove the entries affected by a new commit of `repo`.
+    ///
+    /// With `changed_paths`, only the entries derived from those files are
+    /// removed; without, every entry of the repository and every derived
+    /// ticker, index and catalog entry is.
+    pub async fn invalidate_changes(
+        &self,
+        repo: &RepoConfig,
+        changed_paths: Option<&[String]>,
+    ) -> anyhow::Result<usize> {
+        let content_prefix = format!("v1:gh:{}:{}:{}:", repo.source, repo.owner, repo.repo);
+        let deleted = match changed_paths {
+            Some(paths) => self.invalidate_paths(&content_prefix, paths).await?,
+            None => {
+                let mut deleted = 0;
+                for pattern in [
+                    format!("{}*", content_prefix),
+                    "v1:ticker:*".to_string(),
+                    "v1:index:*".to_string(),
+                    "v1:catalog:*".to_string(),
+                ] {
+                    deleted += self.delete_matching(&pattern, |_| true).await?;
+                }
+                deleted
+            }
+        };
+
+        info!(
+            "Invalidated {} cache keys after new commit of {}/{}",
+            deleted, repo.owner, repo.repo
+        );
+        metrics::counter!("cache_invalidated_keys_total").increment(deleted as u64);
+        Ok(deleted)
+    }
+
+    /// Remove the entries derived from the files at `paths`.
+    async fn invalidate_paths(
+        &self,
+        content_prefix: &str,
+        paths: &[String],
+    ) -> anyhow::Result<usize> {
+        // Content entries: each file, and the listings and aggregations of its directories
+        let mut exact = BTreeSet::new();
+        let mut patterns = BTreeSet::new();
+        let mut tokens = BTreeSet::new();
+        let mut earliest: Option<Option<NaiveDate>> = None;
+        for path in paths {
+            let path = path.trim_matches('/');
+            exact.insert(format!("{}{}", content_prefix, path));
+            let mut dir = path;
+            while let Some((parent, _)) = dir.rsplit_once('/') {
+                exact.insert(format!("{}{}", content_prefix, parent));
+                patterns.insert(format!("{}{}:agg=true:*", content_prefix, parent));
+                dir = parent;
+            }
+
+            if let Some(token) = path.strip_prefix("data/").and_then(|p| p.split('/').next()) {
+                tokens.insert(token.to_lowercase());
+            }
+            let date = newest_date(path);
+            earliest = Some(match earliest {
+                None => date,
+                Some(current) => current.zip(date).map(|(a, b)| a.min(b)),
+            });
+        }
+        let earliest = earliest.flatten();
+
+        let mut deleted = self
+            .delete_keys(exact.into_iter().collect(), |_| true)
+            .await;
+        for pattern in patterns {
+            deleted += self.delete_matching(&pattern, |_| true).await?;
+        }
+
+        // Derived entries of the affected tokens, except ranges ending before the change
+        if !tokens.is_empty() {
+            deleted += self
+                .delete_keys(vec!["v1:catalog:tokens".to_string()], |_| true)
+                .await;
+        }
+        for token in tokens {
+            for kind in ["ticker", "index", "catalog"] {
+                let pattern = format!("v1:{}:{}:*", kind, token);
+                deleted += self
+                    .delete_matching(&pattern, |key| match (newest_date(key), earliest) {
+                        (Some(last), Some(changed)) => last >= changed,
+                        _ => true,
+                    })
+                    .await?;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Delete the keys matching `pattern` that are accepted by `filter`.
+    async fn delete_matching(
+        &self,
+        pattern: &str,
+        filter: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<usize> {
+        let keys = self.cache_repo.scan(pattern, MAX_INVALIDATE_KEYS).await?;
+        Ok(self.delete_keys(keys, filter).await)
+    }
+
+    /// Delete the `keys` accepted by `filter`, returning how many existed.
+    async fn delete_keys(&self, keys: Vec<String>, filter: impl Fn(&str) -> bool) -> usize {
+        futures::stream::iter(keys.into_iter().filter(|key| filter(key)))
+            .map(|key| {
+                let cache_repo = self.cache_repo.clone();
+                async move { cache_repo.delete(&key).await.unwrap_or(false) }
+            })
+            .buffer_unordered(DELETE_CONCURRENCY)
+            .filter(|existed| std::future::ready(*existed))
+            .count()
+            .await
+    }
+}
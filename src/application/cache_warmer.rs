@@ -0,0 +1,256 @@
+//! Proactive cache warming for frequently requested tickers.
+//!
+//! Ticker responses are fresh for the cache's soft TTL and otherwise only
+//! recomputed on demand. `CacheWarmer` recomputes the configured stats and
+//! history requests every `interval_secs`, so they are refreshed before they
+//! go stale.
+//!
+//! Every replica runs the warmer. Each cycle starts after a random delay of up
+//! to `jitter_secs`, and the first replica to take the shared Redis lock does
+//! the work; the lock lasts for `interval_secs`, so the others skip the cycle.
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! warmer:
+//!   enabled: true
+//!   interval_secs: 240
+//!   jitter_secs: 30
+//!   targets:
+//!     - token: kaspa
+//!       ranges: ["today", "7d"]
+//!       resolutions: ["1h"]
+//! ```
+
+use crate::application::ticker_service::{
+    max_history_span_days, DateWindow, HistoryGrouping, TickerService, HISTORY_RESOLUTIONS,
+};
+use crate::application::QuoteService;
+use crate::domain::{random_up_to, CacheRepository};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Redis key of the lock electing the replica that warms a cycle.
+const WARMER_LOCK_KEY: &str = "v1:warmer:lock";
+
+/// Warmer settings loaded from the `warmer` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WarmerConfig {
+    /// Run the warmer (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between warming cycles; keep `interval_secs + jitter_secs`
+    /// below the cache's soft TTL (default: 240)
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Maximum random delay, in seconds, added before each cycle (default: 30)
+    #[serde(default = "default_jitter_secs")]
+    pub jitter_secs: u64,
+    /// Requests to keep warm
+    #[serde(default)]
+    pub targets: Vec<WarmTarget>,
+}
+
+fn default_interval_secs() -> u64 {
+    240
+}
+fn default_jitter_secs() -> u64 {
+    30
+}
+
+impl Default for WarmerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_interval_secs(),
+            jitter_secs: default_jitter_secs(),
+            targets: Vec::new(),
+        }
+    }
+}
+
+/// A token whose default stats and history requests are kept warm.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WarmTarget {
+    /// Token symbol/name
+    pub token: String,
+    /// Ranges warmed for stats and history (default: ["today"])
+    #[serde(default = "default_ranges")]
+    pub ranges: Vec<String>,
+    /// History resolutions warmed for each range (default: ["1h"])
+    #[serde(default = "default_resolutions")]
+    pub resolutions: Vec<String>,
+}
+
+fn default_ranges() -> Vec<String> {
+    vec!["today".to_string()]
+}
+fn default_resolutions() -> Vec<String> {
+    vec!["1h".to_string()]
+}
+
+/// Background task refreshing cached ticker responses before they expire.
+pub struct CacheWarmer {
+    ticker_service: Arc<TickerService>,
+    quote_service: Arc<QuoteService>,
+    cache_repo: Arc<dyn CacheRepository>,
+    settings: WarmerConfig,
+    /// Identifies this replica as the lock owner
+    instance_id: String,
+}
+
+impl CacheWarmer {
+    pub fn new(
+        ticker_service: Arc<TickerService>,
+        quote_service: Arc<QuoteService>,
+        cache_repo: Arc<dyn CacheRepository>,
+        settings: WarmerConfig,
+    ) -> Self {
+        Self {
+            ticker_service,
+            quote_service,
+            cache_repo,
+            settings,
+            instance_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Spawn the warming loop.
+    ///
+    /// Does nothing when the warmer is disabled or has no targets.
+    pub fn spawn(self: Arc<Self>) {
+        if !self.settings.enabled || self.settings.targets.is_empty() {
+            info!("Cache warmer disabled");
+            return;
+        }
+        let interval = Duration::from_secs(self.settings.interval_secs.max(1));
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(self.jitter()).await;
+                self.run_cycle().await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+
+    /// Random delay in `[0, jitter_secs]`, so replicas do not wake in lockstep.
+    fn jitter(&self) -> Duration {
+        Duration::from_millis(random_up_to(self.settings.jitter_secs.saturating_mul(1000)))
+    }
+
+    /// Warm every target if this replica wins the cycle's lock.
+    async fn run_cycle(&self) {
+        let lock_ttl = self.settings.interval_secs.max(1);
+        match self
+            .cache_repo
+            .try_lock(WARMER_LOCK_KEY, &self.instance_id, lock_ttl)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                metrics::counter!("cache_warmer_cycles_total", "result" => "skipped").increment(1);
+                return;
+            }
+            Err(e) => {
+                warn!("Cache warmer lock failed: {}", e);
+                metrics::counter!("cache_warmer_cycles_total", "result" => "error").increment(1);
+                return;
+            }
+        }
+
+        let started = Instant::now();
+        let mut warmed = 0;
+        let mut failed = 0;
+        for target in &self.settings.targets {
+            let (ok, err) = self.warm_target(target).await;
+            warmed += ok;
+            failed += err;
+        }
+
+        info!(
+            "Cache warmer refreshed {} entries ({} failed) in {}ms",
+            warmed,
+            failed,
+            started.elapsed().as_millis()
+        );
+        metrics::counter!("cache_warmer_cycles_total", "result" => "ran").increment(1);
+        metrics::counter!("cache_warmer_entries_total", "result" => "ok").increment(warmed);
+        metrics::counter!("cache_warmer_entries_total", "result" => "error").increment(failed);
+    }
+
+    /// Refresh the stats and history entries of one target.
+    ///
+    /// Entries are warmed with the parameters of a default request (reference
+    /// quote, flagged venues excluded, automatic exchange selection, composite
+    /// series) so they match the keys regular requests read. Returns the number
+    /// of entries refreshed and failed.
+    async fn warm_target(&self, target: &WarmTarget) -> (u64, u64) {
+        let token = target.token.to_lowercase();
+        let quote = self.quote_service.reference().to_string();
+        let (mut warmed, mut failed) = (0, 0);
+
+        for range in &target.ranges {
+            let window = match DateWindow::resolve(Some(range), None, None, "today") {
+                Ok(window) => window,
+                Err(e) => {
+                    warn!("Cache warmer skipping {} range {}: {}", token, range, e);
+                    continue;
+                }
+            };
+
+            match self
+                .ticker_service
+                .refresh_ticker_stats(token.clone(), window.clone(), false, quote.clone())
+                .await
+            {
+                Ok(_) => warmed += 1,
+                Err(e) => {
+                    warn!("Cache warmer failed stats for {} {}: {}", token, range, e);
+                    failed += 1;
+                }
+            }
+
+            for resolution in &target.resolutions {
+                if !HISTORY_RESOLUTIONS.contains(&resolution.as_str()) {
+                    warn!(
+                        "Cache warmer skipping {}: invalid resolution {}",
+                        token, resolution
+                    );
+                    continue;
+                }
+                if window.span_days() > max_history_span_days(resolution) {
+                    warn!(
+                        "Cache warmer skipping {} {} at {}: window too large",
+                        token, range, resolution
+                    );
+                    continue;
+                }
+                match self
+                    .ticker_service
+                    .refresh_ticker_history(
+                        token.clone(),
+                        window.clone(),
+                        resolution.clone(),
+                        Vec::new(),
+                        HistoryGrouping::Composite,
+                        quote.clone(),
+                    )
+                    .await
+                {
+                    Ok(_) => warmed += 1,
+                    Err(e) => {
+                        warn!(
+                            "Cache warmer failed history for {} {} at {}: {}",
+                            token, range, resolution, e
+                        );
+                        failed += 1;
+                    }
+                }
+            }
+        }
+
+        (warmed, failed)
+    }
+}
@@ -0,0 +1,525 @@
+//! Background index of the data tree.
+//!
+//! `CatalogIndex` periodically walks `data/{token}/{exchange}/{year}/{month}/`
+//! of the default repository and keeps an in-memory snapshot of which day files
+//! exist, with their kinds and sizes. Each token is read with a single tree
+//! listing (see [`ContentRepository::list_tree`]). The snapshot is persisted to Redis so a
+//! restarted instance can use it before its first walk completes.
+//!
+//! Services consult the snapshot before fetching: exchange lists come from it,
+//! and day files it knows to be missing are not requested at all. Because new
+//! files appear continuously, "missing" is only trusted for days that were
+//! complete when the snapshot was built (see `COMPLETE_AFTER_DAYS`); anything
+//! newer, and any token the snapshot does not know, is fetched as before.
+//!
+//! New commits reported by the webhook or the git mirror refresh the tokens
+//! they touch (see [`CatalogIndex::refresh_changes`]), so a backfilled day is
+//! not reported missing until the next walk.
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! catalog:
+//!   enabled: true
+//!   refresh_interval_secs: 900
+//! ```
+
+use crate::application::catalog_service::{classify_file, FILE_KIND_RAW, FILE_KIND_SUMMARY};
+use crate::domain::{CacheRepository, Content, ContentRepository, ContentType, RepoConfig};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+use tracing::{error, info, warn};
+
+/// Redis key holding the persisted snapshot.
+const SNAPSHOT_CACHE_KEY: &str = "v1:catalog:index";
+
+/// Seconds the persisted snapshot is kept in Redis.
+const SNAPSHOT_CACHE_TTL: u64 = 7 * 86_400;
+
+/// Days that must have passed since a date, as of the snapshot build, before
+/// the snapshot is trusted to list all of its files.
+const COMPLETE_AFTER_DAYS: i64 = 2;
+
+/// Catalog settings loaded from the `catalog` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CatalogConfig {
+    /// Build and consult the index (default: true)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Seconds between walks of the data tree (default: 900)
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_refresh_interval_secs() -> u64 {
+    900
+}
+
+impl Default for CatalogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            refresh_interval_secs: default_refresh_interval_secs(),
+        }
+    }
+}
+
+/// Files present for one day, with their sizes in bytes (0 when the backend
+/// does not report sizes).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DayFiles {
+    /// `YYYY-MM-DD.json` daily summary
+    pub summary: Option<u64>,
+    /// `YYYY-MM-DD-raw.json` tick file
+    pub raw: Option<u64>,
+}
+
+impl DayFiles {
+    /// File kinds present, in alphabetical order.
+    pub fn kinds(&self) -> Vec<&'static str> {
+        let mut kinds = Vec::new();
+        if self.raw.is_some() {
+            kinds.push(FILE_KIND_RAW);
+        }
+        if self.summary.is_some() {
+            kinds.push(FILE_KIND_SUMMARY);
+        }
+        kinds
+    }
+}
+
+/// Day files of one exchange, by date.
+pub type ExchangeDays = BTreeMap<NaiveDate, DayFiles>;
+
+/// Point-in-time view of the data tree: token → exchange → date → files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogSnapshot {
+    /// When the walk that produced this snapshot started
+    pub built_at: DateTime<Utc>,
+    pub tokens: BTreeMap<String, BTreeMap<String, ExchangeDays>>,
+}
+
+/// Answer to "does this file exist?" from a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileLookup {
+    /// The snapshot lists the file
+    Present,
+    /// The snapshot covers the day and the file is not in it
+    Absent,
+    /// The snapshot cannot tell (unknown token, recent day, non-data path)
+    Unknown,
+}
+
+impl CatalogSnapshot {
+    /// Exchanges carrying `token`, sorted, if the token is indexed.
+    pub fn exchanges(&self, token: &str) -> Option<Vec<String>> {
+        self.tokens
+            .get(token)
+            .map(|exchanges| exchanges.keys().cloned().collect())
+    }
+
+    /// Day files of `token` on `exchange`, if the pair is indexed.
+    pub fn days(&self, token: &str, exchange: &str) -> Option<&ExchangeDays> {
+        self.tokens.get(token)?.get(exchange)
+    }
+
+    /// Whether a day file of the given kind exists.
+    pub fn lookup(&self, token: &str, exchange: &str, date: NaiveDate, kind: &str) -> FileLookup {
+        let Some(exchanges) = self.tokens.get(token) else {
+            return FileLookup::Unknown;
+        };
+        let files = exchanges.get(exchange).and_then(|days| days.get(&date));
+        let present = match kind {
+            FILE_KIND_RAW => files.is_some_and(|f| f.raw.is_some()),
+            _ => files.is_some_and(|f| f.summary.is_some()),
+        };
+        if present {
+            FileLookup::Present
+        } else if date <= self.built_at.date_naive() - Duration::days(COMPLETE_AFTER_DAYS) {
+            FileLookup::Absent
+        } else {
+            FileLookup::Unknown
+        }
+    }
+}
+
+/// Periodically rebuilt index of the default repository's data tree.
+pub struct CatalogIndex {
+    content_repo: Arc<dyn ContentRepository>,
+    cache_repo: Arc<dyn CacheRepository>,
+    repo: RepoConfig,
+    settings: CatalogConfig,
+    snapshot: RwLock<Option<Arc<CatalogSnapshot>>>,
+}
+
+impl CatalogIndex {
+    pub fn new(
+        content_repo: Arc<dyn ContentRepository>,
+        cache_repo: Arc<dyn CacheRepository>,
+        repo: RepoConfig,
+        settings: CatalogConfig,
+    ) -> Self {
+        Self {
+            content_repo,
+            cache_repo,
+            repo,
+            settings,
+            snapshot: RwLock::new(None),
+        }
+    }
+
+    /// Latest snapshot, once one has been built or restored.
+    pub fn snapshot(&self) -> Option<Arc<CatalogSnapshot>> {
+        self.snapshot.read().ok()?.clone()
+    }
+
+    /// Exchanges carrying `token`, from the snapshot when the token is indexed
+    /// and from a directory listing otherwise.
+    pub async fn list_exchanges(&self, token: &str) -> anyhow::Result<Vec<String>> {
+        let token = token.to_lowercase();
+        if let Some(exchanges) = self.snapshot().and_then(|s| s.exchanges(&token)) {
+            return Ok(exchanges);
+        }
+        list_dirs(&self.content_repo, &self.repo, &format!("data/{}", token)).await
+    }
+
+    /// Look up a repository path such as `data/kaspa/ascendex/2025/12/2025-12-30-raw.json`.
+    ///
+    /// Only paths of the indexed repository that name a day file can be answered.
+    pub fn lookup_path(&self, config: &RepoConfig, path: &str) -> FileLookup {
+        if !self.indexes(config) {
+            return FileLookup::Unknown;
+        }
+        let Some(snapshot) = self.snapshot() else {
+            return FileLookup::Unknown;
+        };
+
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let ["data", token, exchange, _year, _month, file] = parts.as_slice() else {
+            return FileLookup::Unknown;
+        };
+        let Some((date, kind)) = classify_file(file) else {
+            return FileLookup::Unknown;
+        };
+
+        let result = snapshot.lookup(token, exchange, date, kind);
+        if result == FileLookup::Absent {
+            metrics::counter!("catalog_fetches_skipped_total").increment(1);
+        }
+        result
+    }
+
+    /// Whether `config` is the repository this index walks.
+    fn indexes(&self, config: &RepoConfig) -> bool {
+        config.source == self.repo.source
+            && config.owner == self.repo.owner
+            && config.repo == self.repo.repo
+    }
+
+    /// Load the snapshot persisted by a previous walk, if any.
+    pub async fn restore(&self) {
+        let Ok(Some(cached)) = self.cache_repo.get(SNAPSHOT_CACHE_KEY).await else {
+            return;
+        };
+        match serde_json::from_str::<CatalogSnapshot>(&cached) {
+            Ok(snapshot) => {
+                info!(
+                    "Restored catalog index built at {} ({} tokens)",
+                    snapshot.built_at,
+                    snapshot.tokens.len()
+                );
+                self.replace(snapshot);
+            }
+            Err(e) => warn!("Ignoring unreadable catalog index in cache: {}", e),
+        }
+    }
+
+    /// Walk the data tree and replace the snapshot.
+    ///
+    /// A token whose listing fails keeps its entries from the previous
+    /// snapshot, or is left out until the next rebuild if it had none.
+    pub async fn rebuild(&self) -> anyhow::Result<()> {
+        let built_at = Utc::now();
+        let mut tokens = BTreeMap::new();
+
+        // One tree listing per token, read concurrently
+        let names = list_dirs(&self.content_repo, &self.repo, "data").await?;
+        let listed: Vec<_> = futures::stream::iter(names)
+            .map(|token| async move {
+                let items = self
+                    .content_repo
+                    .list_tree(&self.repo, &format!("data/{}", token))
+                    .await;
+                (token, items)
+            })
+            .buffer_unordered(4)
+            .collect()
+            .await;
+
+        let previous = self.snapshot();
+        for (token, items) in listed {
+            match items {
+                Ok(items) => {
+                    tokens.insert(token, index_exchanges(&items));
+                }
+                Err(e) => {
+                    warn!("Failed to list data/{} for catalog index: {}", token, e);
+                    if let Some(exchanges) = previous.as_ref().and_then(|s| s.tokens.get(&token)) {
+                        tokens.insert(token, exchanges.clone());
+                    }
+                }
+            }
+        }
+
+        let snapshot = CatalogSnapshot { built_at, tokens };
+        self.persist(&snapshot).await;
+        info!(
+            "Catalog index rebuilt: {} tokens in {}ms",
+            snapshot.tokens.len(),
+            Utc::now()
+                .signed_duration_since(built_at)
+                .num_milliseconds()
+        );
+        self.replace(snapshot);
+        Ok(())
+    }
+
+    /// Bring the snapshot up to date after a new commit of `repo`.
+    ///
+    /// With `changed_paths`, only the tokens those files belong to are listed
+    /// again and the rest of the snapshot is kept as is; without, the whole
+    /// tree is walked. Commits to other repositories are ignored, as are
+    /// commits arriving before the first snapshot exists.
+    pub async fn refresh_changes(
+        &self,
+        repo: &RepoConfig,
+        changed_paths: Option<&[String]>,
+    ) -> anyhow::Result<()> {
+        if !self.settings.enabled || !self.indexes(repo) {
+            return Ok(());
+        }
+        let Some(previous) = self.snapshot() else {
+            return Ok(());
+        };
+        let Some(paths) = changed_paths else {
+            return self.rebuild().await;
+        };
+
+        let changed: BTreeSet<String> = paths
+            .iter()
+            .filter_map(|path| {
+                let (token, _) = path
+                    .trim_matches('/')
+                    .strip_prefix("data/")?
+                    .split_once('/')?;
+                Some(token.to_lowercase())
+            })
+            .collect();
+        if changed.is_empty() {
+            return Ok(());
+        }
+
+        let mut tokens = previous.tokens.clone();
+        for token in &changed {
+            match self
+                .content_repo
+                .list_tree(&self.repo, &format!("data/{}", token))
+                .await
+            {
+                Ok(items) => {
+                    tokens.insert(token.clone(), index_exchanges(&items));
+                }
+                Err(e) => warn!("Failed to list data/{} for catalog index: {}", token, e),
+            }
+        }
+
+        // Keep the original build time: tokens not listed again are only as
+        // complete as the walk that produced them
+        let snapshot = CatalogSnapshot {
+            built_at: previous.built_at,
+            tokens,
+        };
+        self.persist(&snapshot).await;
+        info!("Catalog index refreshed for {:?}", changed);
+        self.replace(snapshot);
+        Ok(())
+    }
+
+    /// Store `snapshot` in Redis for restarted instances.
+    async fn persist(&self, snapshot: &CatalogSnapshot) {
+        if let Ok(json) = serde_json::to_string(snapshot) {
+            let _ = self
+                .cache_repo
+                .set(SNAPSHOT_CACHE_KEY, &json, SNAPSHOT_CACHE_TTL)
+                .await;
+        }
+    }
+
+    fn replace(&self, snapshot: CatalogSnapshot) {
+        if let Ok(mut current) = self.snapshot.write() {
+            *current = Some(Arc::new(snapshot));
+        }
+    }
+
+    /// Spawn the background task that restores and then periodically rebuilds the index.
+    ///
+    /// Does nothing when the catalog is disabled.
+    pub fn spawn_refresh_loop(self: Arc<Self>) {
+        if !self.settings.enabled {
+            info!("Catalog index disabled");
+            return;
+        }
+        let interval = std::time::Duration::from_secs(self.settings.refresh_interval_secs.max(1));
+        tokio::spawn(async move {
+            self.restore().await;
+            loop {
+                match self.rebuild().await {
+                    Ok(()) => {
+                        metrics::counter!("catalog_index_builds_total", "result" => "ok")
+                            .increment(1);
+                    }
+                    Err(e) => {
+                        error!("Catalog index rebuild failed: {}", e);
+                        metrics::counter!("catalog_index_builds_total", "result" => "error")
+                            .increment(1);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
+
+/// Names of the subdirectories of `path`, sorted.
+pub(crate) async fn list_dirs(
+    repo: &Arc<dyn ContentRepository>,
+    config: &RepoConfig,
+    path: &str,
+) -> anyhow::Result<Vec<String>> {
+    let mut dirs: Vec<String> = repo
+        .list_directory(config, path)
+        .await?
+        .into_iter()
+        .filter(|e| e.item_type == ContentType::Dir)
+        .map(|e| e.name)
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// List every day file of `token` on `exchange` with one tree listing.
+pub(crate) async fn walk_exchange(
+    repo: &Arc<dyn ContentRepository>,
+    config: &RepoConfig,
+    token: &str,
+    exchange: &str,
+) -> anyhow::Result<ExchangeDays> {
+    let items = repo
+        .list_tree(config, &format!("data/{}/{}", token, exchange))
+        .await?;
+    Ok(index_exchanges(&items)
+        .into_values()
+        .next()
+        .unwrap_or_default())
+}
+
+/// Group the day files of a tree listing by exchange.
+///
+/// `items` is a [`ContentRepository::list_tree`] listing of either a token
+/// directory (`data/{token}`) or one of its exchange directories; in the
+/// latter case the result has a single entry.
+fn index_exchanges(items: &[Content]) -> BTreeMap<String, ExchangeDays> {
+    let mut exchanges: BTreeMap<String, ExchangeDays> = BTreeMap::new();
+    for item in items {
+        let parts: Vec<&str> = item.path.trim_matches('/').split('/').collect();
+        match (parts.as_slice(), &item.item_type) {
+            (["data", _token, exchange], ContentType::Dir) => {
+                exchanges.entry(exchange.to_string()).or_default();
+            }
+            (["data", _token, exchange, _year, _month, file], ContentType::File) => {
+                let Some((date, kind)) = classify_file(file) else {
+                    continue;
+                };
+                let files = exchanges
+                    .entry(exchange.to_string())
+                    .or_default()
+                    .entry(date)
+                    .or_default();
+                let size = Some(item.size.unwrap_or(0));
+                if kind == FILE_KIND_RAW {
+                    files.raw = size;
+                } else {
+                    files.summary = size;
+                }
+            }
+            _ => {}
+        }
+    }
+    exchanges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{FilesystemRepository, MemoryCacheRepository};
+    use std::path::Path;
+
+    const DAY_DIR: &str = "data/kaspa/ascendex/2025/12";
+
+    fn write_day(root: &Path, file: &str) {
+        std::fs::create_dir_all(root.join(DAY_DIR)).unwrap();
+        std::fs::write(root.join(DAY_DIR).join(file), r#"{"close": 0.05}"#).unwrap();
+    }
+
+    #[tokio::test]
+    async fn refresh_picks_up_backfilled_days() {
+        let root = std::env::temp_dir().join(format!("catalog-{}", uuid::Uuid::new_v4()));
+        write_day(&root, "2025-12-01.json");
+        let repo = RepoConfig {
+            source: "local".to_string(),
+            owner: "KaspaDev".to_string(),
+            repo: "Kaspa-Exchange-Data".to_string(),
+            root: Some(root.to_string_lossy().into_owned()),
+        };
+        let catalog = CatalogIndex::new(
+            Arc::new(FilesystemRepository::new(&root)),
+            Arc::new(MemoryCacheRepository::new(1 << 20)),
+            repo.clone(),
+            CatalogConfig::default(),
+        );
+        catalog.rebuild().await.unwrap();
+
+        let backfilled = format!("{}/2025-12-02.json", DAY_DIR);
+        assert_eq!(catalog.lookup_path(&repo, &backfilled), FileLookup::Absent);
+        write_day(&root, "2025-12-02.json");
+
+        // Commits to other repositories leave the snapshot alone
+        let other = RepoConfig {
+            repo: "Other".to_string(),
+            ..repo.clone()
+        };
+        let changed = vec![backfilled.clone()];
+        catalog
+            .refresh_changes(&other, Some(&changed))
+            .await
+            .unwrap();
+        assert_eq!(catalog.lookup_path(&repo, &backfilled), FileLookup::Absent);
+
+        catalog
+            .refresh_changes(&repo, Some(&changed))
+            .await
+            .unwrap();
+        assert_eq!(catalog.lookup_path(&repo, &backfilled), FileLookup::Present);
+        let date = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        let days = catalog.snapshot().unwrap();
+        assert!(days.days("kaspa", "ascendex").unwrap().contains_key(&date));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
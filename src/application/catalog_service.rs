@@ -0,0 +1,264 @@
+//! Catalog service for discovering what data exists.
+//!
+//! Answers from the background `CatalogIndex` when it has been built, and
+//! otherwise walks the `data/{token}/{exchange}/{year}/{month}/` tree with
+//! `ContentRepository::list_directory`, so clients can find tokens, the
+//! exchanges carrying them and the dates covered, without probing paths.
+//! Walked listings are cached in Redis for `CATALOG_CACHE_TTL` seconds.
+
+use crate::application::catalog_index::{list_dirs, walk_exchange, CatalogIndex, ExchangeDays};
+use crate::domain::{CacheRepository, ContentRepository, RepoConfig};
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::Arc;
+use tracing::info;
+use utoipa::ToSchema;
+
+/// Seconds catalog listings stay cached.
+const CATALOG_CACHE_TTL: u64 = 900;
+
+/// File kind of a daily `YYYY-MM-DD.json` summary.
+pub const FILE_KIND_SUMMARY: &str = "summary";
+/// File kind of a `YYYY-MM-DD-raw.json` tick file.
+pub const FILE_KIND_RAW: &str = "raw";
+
+/// Response structure for the token list endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokensResponse {
+    /// Tokens with a directory under `data/`, in alphabetical order
+    pub tokens: Vec<String>,
+    /// Number of tokens
+    pub count: usize,
+}
+
+/// Response structure for the token exchanges endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExchangesResponse {
+    /// Token symbol/name
+    pub token: String,
+    /// Exchanges with data for the token, in alphabetical order
+    pub exchanges: Vec<String>,
+    /// Number of exchanges
+    pub count: usize,
+}
+
+/// Response structure for the exchange coverage endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CoverageResponse {
+    /// Token symbol/name
+    pub token: String,
+    /// Exchange identifier
+    pub exchange: String,
+    /// First day with any file (YYYY-MM-DD)
+    pub first_date: Option<String>,
+    /// Last day with any file (YYYY-MM-DD)
+    pub last_date: Option<String>,
+    /// Number of days with at least one file
+    pub day_count: usize,
+    /// File kinds present anywhere in the range (`summary`, `raw`)
+    pub file_kinds: Vec<String>,
+    /// Coverage by year
+    pub years: Vec<YearCoverage>,
+}
+
+/// Days covered within one year.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct YearCoverage {
+    /// Year (YYYY)
+    pub year: String,
+    /// Coverage by month
+    pub months: Vec<MonthCoverage>,
+}
+
+/// Days covered within one month.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MonthCoverage {
+    /// Month (MM)
+    pub month: String,
+    /// Days with at least one file
+    pub days: Vec<DayCoverage>,
+}
+
+/// Files available for a single day.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DayCoverage {
+    /// Day (YYYY-MM-DD)
+    pub date: String,
+    /// File kinds present for the day (`summary`, `raw`)
+    pub kinds: Vec<String>,
+}
+
+/// Whether `name` is usable as a single token or exchange path segment.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Classify a data file name as `(date, kind)`.
+///
+/// Returns `None` for anything other than `YYYY-MM-DD.json` and `YYYY-MM-DD-raw.json`.
+pub fn classify_file(name: &str) -> Option<(NaiveDate, &'static str)> {
+    let stem = name.strip_suffix(".json")?;
+    let (date, kind) = match stem.strip_suffix("-raw") {
+        Some(date) => (date, FILE_KIND_RAW),
+        None => (stem, FILE_KIND_SUMMARY),
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some((date, kind))
+}
+
+/// Service answering discovery queries over the data tree.
+#[derive(Clone)]
+pub struct CatalogService {
+    content_repo: Arc<dyn ContentRepository>,
+    cache_repo: Arc<dyn CacheRepository>,
+    default_repo: RepoConfig,
+    index: Arc<CatalogIndex>,
+}
+
+impl CatalogService {
+    pub fn new(
+        content_repo: Arc<dyn ContentRepository>,
+        cache_repo: Arc<dyn CacheRepository>,
+        default_repo: RepoConfig,
+        index: Arc<CatalogIndex>,
+    ) -> Self {
+        Self {
+            content_repo,
+            cache_repo,
+            default_repo,
+            index,
+        }
+    }
+
+    /// List every token with a directory under `data/`.
+    pub async fn list_tokens(&self) -> anyhow::Result<TokensResponse> {
+        if let Some(snapshot) = self.index.snapshot() {
+            let tokens: Vec<String> = snapshot.tokens.keys().cloned().collect();
+            return Ok(TokensResponse {
+                count: tokens.len(),
+                tokens,
+            });
+        }
+        self.cached("v1:catalog:tokens".to_string(), async {
+            let tokens = list_dirs(&self.content_repo, &self.default_repo, "data").await?;
+            Ok(TokensResponse {
+                count: tokens.len(),
+                tokens,
+            })
+        })
+        .await
+    }
+
+    /// List the exchanges carrying a token.
+    pub async fn list_exchanges(&self, token: &str) -> anyhow::Result<ExchangesResponse> {
+        let token = token.to_lowercase();
+        if let Some(exchanges) = self.index.snapshot().and_then(|s| s.exchanges(&token)) {
+            return Ok(ExchangesResponse {
+                token,
+                count: exchanges.len(),
+                exchanges,
+            });
+        }
+        self.cached(format!("v1:catalog:{}:exchanges", token), async {
+            let path = format!("data/{}", token);
+            let exchanges = list_dirs(&self.content_repo, &self.default_repo, &path).await?;
+            Ok(ExchangesResponse {
+                token: token.clone(),
+                count: exchanges.len(),
+                exchanges,
+            })
+        })
+        .await
+    }
+
+    /// Describe which days and file kinds exist for a token on an exchange.
+    pub async fn get_coverage(
+        &self,
+        token: &str,
+        exchange: &str,
+    ) -> anyhow::Result<CoverageResponse> {
+        let token = token.to_lowercase();
+        if let Some(snapshot) = self.index.snapshot() {
+            if let Some(days) = snapshot.days(&token, exchange) {
+                return Ok(Self::build_coverage(&token, exchange, days));
+            }
+        }
+
+        let cache_key = format!("v1:catalog:{}:{}:coverage", token, exchange);
+        self.cached(cache_key, async {
+            let days =
+                walk_exchange(&self.content_repo, &self.default_repo, &token, exchange).await?;
+            Ok(Self::build_coverage(&token, exchange, &days))
+        })
+        .await
+    }
+
+    fn build_coverage(token: &str, exchange: &str, days: &ExchangeDays) -> CoverageResponse {
+        let mut years: Vec<YearCoverage> = Vec::new();
+        for (date, files) in days {
+            let year = date.format("%Y").to_string();
+            let month = date.format("%m").to_string();
+            if years.last().map(|y| &y.year) != Some(&year) {
+                years.push(YearCoverage {
+                    year,
+                    months: Vec::new(),
+                });
+            }
+            let months = &mut years.last_mut().expect("year just pushed").months;
+            if months.last().map(|m| &m.month) != Some(&month) {
+                months.push(MonthCoverage {
+                    month,
+                    days: Vec::new(),
+                });
+            }
+            months
+                .last_mut()
+                .expect("month just pushed")
+                .days
+                .push(DayCoverage {
+                    date: date.to_string(),
+                    kinds: files.kinds().into_iter().map(String::from).collect(),
+                });
+        }
+
+        let file_kinds: BTreeSet<&str> = days.values().flat_map(|f| f.kinds()).collect();
+        CoverageResponse {
+            token: token.to_string(),
+            exchange: exchange.to_string(),
+            first_date: days.keys().next().map(|d| d.to_string()),
+            last_date: days.keys().next_back().map(|d| d.to_string()),
+            day_count: days.len(),
+            file_kinds: file_kinds.into_iter().map(String::from).collect(),
+            years,
+        }
+    }
+
+    /// Serve `key` from the cache, or compute and cache it.
+    async fn cached<T, F>(&self, key: String, compute: F) -> anyhow::Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        if let Ok(Some(cached)) = self.cache_repo.get(&key).await {
+            if let Ok(value) = serde_json::from_str::<T>(&cached) {
+                info!("Cache HIT: {}", key);
+                metrics::counter!("cache_operations_total", "operation" => "hit").increment(1);
+                return Ok(value);
+            }
+        }
+        metrics::counter!("cache_operations_total", "operation" => "miss").increment(1);
+
+        let value = compute.await?;
+        if let Ok(json) = serde_json::to_string(&value) {
+            let _ = self.cache_repo.set(&key, &json, CATALOG_CACHE_TTL).await;
+        }
+        Ok(value)
+    }
+}
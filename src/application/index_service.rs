@@ -0,0 +1,463 @@
+//! Composite price index service.
+//!
+//! Builds a cross-exchange reference price series for a token. At the end of
+//! every bucket each venue contributes its most recent price; venues whose last
+//! update is older than `stale_after_secs` are excluded, outliers are rejected
+//! against the median, and the rest are combined with the requested weighting
+//! (see [`crate::domain::pricing`]).
+//!
+//! Venue prices and volumes are first converted into the reference quote (see
+//! [`crate::application::quote_service`]); venues whose quote is unknown or
+//! cannot be converted are left out.
+
+use crate::application::cache::{Cached, ResponseCache};
+use crate::application::catalog_index::CatalogIndex;
+use crate::application::quote_service::QuoteService;
+use crate::application::ticker_service::{DateWindow, TickerService};
+use crate::domain::pricing::{composite_price, VenueQuote, Weighting};
+use crate::domain::{ContentRepository, RepoConfig, Tick};
+use chrono::{DateTime, Duration, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Index settings loaded from the `index` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct IndexConfig {
+    /// Weighting used when the request does not name one (default: volume)
+    #[serde(default)]
+    pub weighting: Weighting,
+    /// Venues whose last update is older than this are excluded (default: 3600)
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+    /// Maximum deviation from the median, in percent, before a venue is rejected (default: 10)
+    #[serde(default = "default_outlier_threshold_pct")]
+    pub outlier_threshold_pct: f64,
+    /// Maximum number of exchanges read per token (default: 20)
+    #[serde(default = "default_max_exchanges")]
+    pub max_exchanges: usize,
+}
+
+fn default_stale_after_secs() -> u64 {
+    3600
+}
+fn default_outlier_threshold_pct() -> f64 {
+    10.0
+}
+fn default_max_exchanges() -> usize {
+    20
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            weighting: Weighting::default(),
+            stale_after_secs: default_stale_after_secs(),
+            outlier_threshold_pct: default_outlier_threshold_pct(),
+            max_exchanges: default_max_exchanges(),
+        }
+    }
+}
+
+/// Response structure for the composite index endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IndexResponse {
+    /// Token symbol/name
+    pub token: String,
+    /// Range requested (today, 7d, 30d, or `from..to` for custom windows)
+    pub range: String,
+    /// First day covered (YYYY-MM-DD)
+    pub from: String,
+    /// Last day covered (YYYY-MM-DD)
+    pub to: String,
+    /// Data resolution
+    pub resolution: String,
+    /// Weighting applied: volume, equal, or median
+    pub weighting: String,
+    /// Quote asset the index is expressed in
+    pub quote: String,
+    /// Maximum venue update age, in seconds, for inclusion
+    pub stale_after_secs: u64,
+    /// Maximum deviation from the median, in percent, for inclusion
+    pub outlier_threshold_pct: f64,
+    /// Exchanges considered, in alphabetical order
+    pub exchanges: Vec<String>,
+    /// Index data points
+    pub data: Vec<IndexPoint>,
+}
+
+/// Single composite index value.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IndexPoint {
+    /// Unix timestamp (seconds) of the bucket start
+    pub timestamp: i64,
+    /// Composite price at the end of the bucket
+    pub price: f64,
+    /// Venues contributing to `price`
+    pub exchange_count: usize,
+    /// Venues excluded because their last update was too old
+    pub stale: usize,
+    /// Venues excluded as outliers
+    pub outliers: usize,
+}
+
+/// Query parameters for the composite index endpoint.
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+pub struct IndexQuery {
+    /// Lookback range: today, 7d, 30d (default: today)
+    #[param(default = "today", example = "7d")]
+    pub range: Option<String>,
+    /// Window start: YYYY-MM-DD, RFC 3339, or unix timestamp (replaces `range`)
+    #[param(example = "2025-12-01")]
+    pub from: Option<String>,
+    /// Window end, inclusive: same formats as `from` (default: today)
+    #[param(example = "2025-12-31")]
+    pub to: Option<String>,
+    /// Data resolution: 5m, 1h, 1d (default: 1h)
+    #[param(default = "1h", example = "1h")]
+    pub resolution: Option<String>,
+    /// Weighting: volume, equal, median (default: from config)
+    #[param(example = "volume")]
+    pub weighting: Option<String>,
+}
+
+/// A venue's observations, in the quote of its market.
+struct Venue {
+    exchange: String,
+    quote: Option<String>,
+    observations: Vec<Observation>,
+}
+
+/// A venue's price as of `timestamp`.
+struct Observation {
+    /// Unix timestamp (seconds)
+    timestamp: i64,
+    price: f64,
+    /// Rolling 24h quote volume (daily volume for `1d`)
+    volume: Option<f64>,
+}
+
+/// Service computing the composite price index.
+#[derive(Clone)]
+pub struct IndexService {
+    content_repo: Arc<dyn ContentRepository>,
+    cache: ResponseCache,
+    default_repo: RepoConfig,
+    settings: IndexConfig,
+    catalog: Arc<CatalogIndex>,
+    quotes: Arc<QuoteService>,
+}
+
+impl IndexService {
+    pub fn new(
+        content_repo: Arc<dyn ContentRepository>,
+        cache: ResponseCache,
+        default_repo: RepoConfig,
+        settings: IndexConfig,
+        catalog: Arc<CatalogIndex>,
+        quotes: Arc<QuoteService>,
+    ) -> Self {
+        Self {
+            content_repo,
+            cache,
+            default_repo,
+            settings,
+            catalog,
+            quotes,
+        }
+    }
+
+    /// Weighting used when the request does not specify one.
+    pub fn default_weighting(&self) -> Weighting {
+        self.settings.weighting
+    }
+
+    /// Build the composite index for a token over a window.
+    pub async fn get_index(
+        &self,
+        token: String,
+        window: DateWindow,
+        resolution: String,
+        weighting: Weighting,
+    ) -> anyhow::Result<Cached<IndexResponse>> {
+        let cache_key = format!(
+            "v1:index:{}:{}:{}:{}",
+            token,
+            window.label,
+            resolution,
+            weighting.as_str()
+        );
+        let this = self.clone();
+        self.cache
+            .get_or_compute(cache_key, async move {
+                this.build_index(token, window, resolution, weighting).await
+            })
+            .await
+    }
+
+    /// Compute the composite index without reading the cache.
+    async fn build_index(
+        &self,
+        token: String,
+        window: DateWindow,
+        resolution: String,
+        weighting: Weighting,
+    ) -> anyhow::Result<IndexResponse> {
+        // Discover exchanges for this token
+        let mut exchange_dirs = self.catalog.list_exchanges(&token).await?;
+        exchange_dirs.truncate(self.settings.max_exchanges);
+
+        if exchange_dirs.is_empty() {
+            anyhow::bail!("No exchanges found for token: {}", token);
+        }
+
+        let venues: Vec<Venue> = futures::stream::iter(exchange_dirs)
+            .map(|exchange| {
+                let token = token.clone();
+                let window = window.clone();
+                let resolution = resolution.clone();
+                async move {
+                    self.fetch_observations(&token, &exchange, &window, &resolution)
+                        .await
+                }
+            })
+            .buffer_unordered(10)
+            .filter(|venue| futures::future::ready(!venue.observations.is_empty()))
+            .collect()
+            .await;
+        let quote = self.quotes.reference().to_string();
+        let mut venues = self.normalize_venues(venues, &quote, &window).await;
+        venues.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let interval_secs: i64 = match resolution.as_str() {
+            "5m" => 300,
+            "1d" => 86400,
+            _ => 3600,
+        };
+        let data = self.compute_points(&venues, &window, interval_secs, weighting);
+
+        let response = IndexResponse {
+            token: token.clone(),
+            range: window.label.clone(),
+            from: window.start.to_string(),
+            to: window.end.to_string(),
+            resolution: resolution.clone(),
+            weighting: weighting.as_str().to_string(),
+            quote,
+            stale_after_secs: self.settings.stale_after_secs,
+            outlier_threshold_pct: self.settings.outlier_threshold_pct,
+            exchanges: venues.into_iter().map(|(exchange, _)| exchange).collect(),
+            data,
+        };
+
+        info!(
+            "Computed {} index for {} from {} venues",
+            response.resolution,
+            token,
+            response.exchanges.len()
+        );
+        Ok(response)
+    }
+
+    /// Convert every venue's prices and volumes into `quote`.
+    ///
+    /// Observations are converted at their day's rate. Venues whose market
+    /// quote is unknown, or that have no rate for any observation, are dropped.
+    async fn normalize_venues(
+        &self,
+        venues: Vec<Venue>,
+        quote: &str,
+        window: &DateWindow,
+    ) -> Vec<(String, Vec<Observation>)> {
+        let mut assets: Vec<String> = venues.iter().filter_map(|v| v.quote.clone()).collect();
+        assets.push(quote.to_string());
+        assets.sort();
+        assets.dedup();
+        let rates = self.quotes.load_rates(&assets, window).await;
+
+        let mut normalized = Vec::new();
+        for venue in venues {
+            let Some(market_quote) = venue.quote else {
+                warn!(
+                    "Unknown quote for {}, left out of the index",
+                    venue.exchange
+                );
+                continue;
+            };
+            let observations: Vec<Observation> = venue
+                .observations
+                .into_iter()
+                .filter_map(|o| {
+                    let date = DateTime::from_timestamp(o.timestamp, 0)?.date_naive();
+                    let factor = rates.factor(&market_quote, quote, date)?;
+                    Some(Observation {
+                        timestamp: o.timestamp,
+                        price: o.price * factor,
+                        volume: o.volume.map(|v| v * factor),
+                    })
+                })
+                .collect();
+            if observations.is_empty() {
+                warn!(
+                    "No {}/{} rate for {}, left out of the index",
+                    market_quote, quote, venue.exchange
+                );
+                continue;
+            }
+            normalized.push((venue.exchange, observations));
+        }
+        normalized
+    }
+
+    /// Load one venue's price observations over the window, oldest first, with
+    /// the quote of its market when the data identifies it.
+    ///
+    /// Daily indexes use the daily bars (stamped at their last update); finer
+    /// resolutions use the raw ticks.
+    async fn fetch_observations(
+        &self,
+        token: &str,
+        exchange: &str,
+        window: &DateWindow,
+        resolution: &str,
+    ) -> Venue {
+        if resolution == "1d" {
+            let bars = TickerService::fetch_daily_bars(
+                &self.content_repo,
+                &self.default_repo,
+                &self.catalog,
+                token,
+                exchange,
+                window.start,
+                window.end,
+            )
+            .await;
+            let quote = TickerService::market_quote(
+                &self.content_repo,
+                &self.default_repo,
+                &self.catalog,
+                token,
+                exchange,
+                &TickerService::recent_days(window.start, window.end),
+            )
+            .await;
+            let observations = bars
+                .iter()
+                .filter_map(|bar| {
+                    let day_end = (bar.date? + Duration::days(1))
+                        .and_hms_opt(0, 0, 0)?
+                        .and_utc()
+                        .timestamp()
+                        - 1;
+                    Some(Observation {
+                        timestamp: bar.last_update.map(|t| t / 1000).unwrap_or(day_end),
+                        price: bar.close?,
+                        volume: bar.estimated_volume(),
+                    })
+                })
+                .collect();
+            return Venue {
+                exchange: exchange.to_string(),
+                quote,
+                observations,
+            };
+        }
+
+        match TickerService::fetch_exchange_raw_data(
+            self.content_repo.clone(),
+            self.default_repo.clone(),
+            self.catalog.clone(),
+            token.to_string(),
+            exchange.to_string(),
+            window.start,
+            window.end,
+        )
+        .await
+        {
+            Ok(ticks) => Venue {
+                exchange: exchange.to_string(),
+                quote: ticks.iter().rev().find_map(Tick::quote_asset),
+                observations: ticks
+                    .iter()
+                    .filter_map(|tick| {
+                        Some(Observation {
+                            timestamp: tick.timestamp_secs(),
+                            price: tick.price()?,
+                            volume: tick.quote_volume,
+                        })
+                    })
+                    .collect(),
+            },
+            Err(e) => {
+                warn!("Failed to fetch index data from {}: {}", exchange, e);
+                Venue {
+                    exchange: exchange.to_string(),
+                    quote: None,
+                    observations: Vec::new(),
+                }
+            }
+        }
+    }
+
+    /// Evaluate the index at the end of every bucket in the window.
+    ///
+    /// Buckets still in progress are evaluated as of now; buckets without any
+    /// usable venue are omitted.
+    fn compute_points(
+        &self,
+        venues: &[(String, Vec<Observation>)],
+        window: &DateWindow,
+        interval_secs: i64,
+        weighting: Weighting,
+    ) -> Vec<IndexPoint> {
+        let Some(start) = window.start.and_hms_opt(0, 0, 0) else {
+            return Vec::new();
+        };
+        let Some(end) = (window.end + Duration::days(1)).and_hms_opt(0, 0, 0) else {
+            return Vec::new();
+        };
+        let now = Utc::now().timestamp();
+        let end = end.and_utc().timestamp().min(now);
+        let stale_after = self.settings.stale_after_secs as i64;
+
+        let mut points = Vec::new();
+        let mut bucket = start.and_utc().timestamp();
+        while bucket < end {
+            let as_of = (bucket + interval_secs).min(now);
+            let mut quotes = Vec::new();
+            let mut stale = 0;
+
+            for (_, observations) in venues {
+                let idx = observations.partition_point(|o| o.timestamp < as_of);
+                let Some(latest) = idx.checked_sub(1).map(|i| &observations[i]) else {
+                    continue;
+                };
+                if as_of - latest.timestamp > stale_after {
+                    stale += 1;
+                    continue;
+                }
+                quotes.push(VenueQuote {
+                    price: latest.price,
+                    volume: latest.volume,
+                });
+            }
+
+            if let Some(composite) =
+                composite_price(&quotes, weighting, self.settings.outlier_threshold_pct)
+            {
+                points.push(IndexPoint {
+                    timestamp: bucket,
+                    price: composite.price,
+                    exchange_count: composite.included,
+                    stale,
+                    outliers: composite.outliers,
+                });
+            }
+            bucket += interval_secs;
+        }
+
+        points
+    }
+}
@@ -1,4 +1,20 @@
+pub mod cache;
+pub mod cache_admin;
+pub mod cache_warmer;
+pub mod catalog_index;
+pub mod catalog_service;
+pub mod index_service;
+pub mod quote_service;
 pub mod service;
 pub mod ticker_service;
+pub mod webhook;
+pub use cache::ResponseCache;
+pub use cache_admin::CacheAdminService;
+pub use cache_warmer::CacheWarmer;
+pub use catalog_index::CatalogIndex;
+pub use catalog_service::CatalogService;
+pub use index_service::IndexService;
+pub use quote_service::QuoteService;
 pub use service::ContentService;
 pub use ticker_service::TickerService;
+pub use webhook::WebhookService;
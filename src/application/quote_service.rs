@@ -0,0 +1,243 @@
+//! Quote-currency normalization.
+//!
+//! Exchanges list the same token against different quote assets (`USDT`,
+//! `USDC`, `BTC`, ...). `QuoteService` converts prices between quotes so that
+//! venues can be compared and aggregated:
+//!
+//! - configured stablecoins are treated as equal to the reference quote
+//! - other assets (e.g. `BTC`, `KAS`) are valued through the repository's own
+//!   series for that asset (`data/btc`, `data/kaspa`): the median daily close
+//!   across the first `reference_exchanges` of its exchanges with data, in
+//!   catalog order
+//!
+//! Rates are daily, so intraday prices are converted at their day's close.
+//! Each series is cached under `v1:ticker:{token}:rates:{start}:{end}`, so
+//! ranges of past days get the historical TTL.
+
+use crate::application::cache::ResponseCache;
+use crate::application::catalog_index::CatalogIndex;
+use crate::application::ticker_service::{DateWindow, TickerService};
+use crate::domain::pricing::median;
+use crate::domain::{ContentRepository, RepoConfig};
+use chrono::{Duration, NaiveDate};
+use futures::StreamExt;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Days before the window loaded for rate series, so a missing first day can
+/// fall back to the previous close.
+const RATE_LOOKBACK_DAYS: i64 = 3;
+
+/// Quote settings loaded from the `quotes` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct QuoteConfig {
+    /// Quote prices are normalized to by default (default: "USDT")
+    #[serde(default = "default_reference")]
+    pub reference: String,
+    /// Assets treated as equal to `reference`
+    #[serde(default = "default_stablecoins")]
+    pub stablecoins: Vec<String>,
+    /// Repository token holding the `reference`-quoted series of each other asset
+    #[serde(default = "default_series")]
+    pub series: HashMap<String, String>,
+    /// Exchanges of a series used to derive its daily rate (default: 5)
+    #[serde(default = "default_reference_exchanges")]
+    pub reference_exchanges: usize,
+}
+
+fn default_reference() -> String {
+    "USDT".to_string()
+}
+fn default_stablecoins() -> Vec<String> {
+    ["USDT", "USDC", "USD", "DAI", "FDUSD", "TUSD", "BUSD"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+fn default_series() -> HashMap<String, String> {
+    HashMap::from([
+        ("BTC".to_string(), "btc".to_string()),
+        ("KAS".to_string(), "kaspa".to_string()),
+    ])
+}
+fn default_reference_exchanges() -> usize {
+    5
+}
+
+impl Default for QuoteConfig {
+    fn default() -> Self {
+        Self {
+            reference: default_reference(),
+            stablecoins: default_stablecoins(),
+            series: default_series(),
+            reference_exchanges: default_reference_exchanges(),
+        }
+    }
+}
+
+/// Daily value of each loaded asset in the reference quote.
+#[derive(Debug, Default)]
+pub struct RateTable {
+    stablecoins: Vec<String>,
+    rates: HashMap<String, BTreeMap<NaiveDate, f64>>,
+}
+
+impl RateTable {
+    /// Value of one unit of `asset` in the reference quote on `date`, using the
+    /// most recent earlier close when the day itself has none.
+    fn value(&self, asset: &str, date: NaiveDate) -> Option<f64> {
+        if self.stablecoins.iter().any(|s| s == asset) {
+            return Some(1.0);
+        }
+        self.rates
+            .get(asset)?
+            .range(..=date)
+            .next_back()
+            .map(|(_, rate)| *rate)
+    }
+
+    /// Factor converting a price quoted in `from` into one quoted in `to` on `date`.
+    ///
+    /// Returns `None` when either asset has no rate for that day.
+    pub fn factor(&self, from: &str, to: &str, date: NaiveDate) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        let from_value = self.value(from, date)?;
+        let to_value = self.value(to, date)?;
+        (to_value > 0.0).then(|| from_value / to_value)
+    }
+}
+
+/// Service converting prices between quote assets.
+#[derive(Clone)]
+pub struct QuoteService {
+    content_repo: Arc<dyn ContentRepository>,
+    default_repo: RepoConfig,
+    settings: QuoteConfig,
+    catalog: Arc<CatalogIndex>,
+    cache: ResponseCache,
+}
+
+impl QuoteService {
+    pub fn new(
+        content_repo: Arc<dyn ContentRepository>,
+        default_repo: RepoConfig,
+        settings: QuoteConfig,
+        catalog: Arc<CatalogIndex>,
+        cache: ResponseCache,
+    ) -> Self {
+        Self {
+            content_repo,
+            default_repo,
+            settings,
+            catalog,
+            cache,
+        }
+    }
+
+    /// Quote used when a request does not name one.
+    pub fn reference(&self) -> &str {
+        &self.settings.reference
+    }
+
+    /// Whether prices can be normalized into `quote`.
+    pub fn is_supported(&self, quote: &str) -> bool {
+        self.settings.stablecoins.iter().any(|s| s == quote)
+            || self.settings.series.contains_key(quote)
+    }
+
+    /// Load daily rates for every asset in `assets` over `window`.
+    ///
+    /// Stablecoins and assets without a configured series need no data; the
+    /// latter simply have no rate in the table.
+    pub async fn load_rates(&self, assets: &[String], window: &DateWindow) -> RateTable {
+        let mut table = RateTable {
+            stablecoins: self.settings.stablecoins.clone(),
+            rates: HashMap::new(),
+        };
+
+        for asset in assets {
+            if table.rates.contains_key(asset) || self.settings.stablecoins.contains(asset) {
+                continue;
+            }
+            let Some(series) = self.settings.series.get(asset) else {
+                continue;
+            };
+            match self.load_series(series, window).await {
+                Ok(rates) => {
+                    table.rates.insert(asset.clone(), rates);
+                }
+                Err(e) => warn!("Failed to load {} rates from {}: {}", asset, series, e),
+            }
+        }
+
+        table
+    }
+
+    /// Median daily close of `token` across its first exchanges with data, cached.
+    async fn load_series(
+        &self,
+        token: &str,
+        window: &DateWindow,
+    ) -> anyhow::Result<BTreeMap<NaiveDate, f64>> {
+        let start = window.start - Duration::days(RATE_LOOKBACK_DAYS);
+        let end = window.end;
+        let cache_key = format!("v1:ticker:{}:rates:{}:{}", token, start, end);
+        let this = self.clone();
+        let token = token.to_string();
+        let cached = self
+            .cache
+            .get_or_compute(cache_key, async move {
+                this.build_series(&token, start, end).await
+            })
+            .await?;
+        Ok(cached.value)
+    }
+
+    /// Median daily close of `token` from `start` to `end`.
+    ///
+    /// Exchanges are tried in catalog order, so the same reference exchanges
+    /// are picked on every load.
+    async fn build_series(
+        &self,
+        token: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> anyhow::Result<BTreeMap<NaiveDate, f64>> {
+        let exchanges = self.catalog.list_exchanges(token).await?;
+
+        let per_exchange: Vec<_> = futures::stream::iter(exchanges)
+            .map(|exchange| async move {
+                TickerService::fetch_daily_bars(
+                    &self.content_repo,
+                    &self.default_repo,
+                    &self.catalog,
+                    token,
+                    &exchange,
+                    start,
+                    end,
+                )
+                .await
+            })
+            .buffered(10)
+            .filter(|bars| futures::future::ready(!bars.is_empty()))
+            .take(self.settings.reference_exchanges)
+            .collect()
+            .await;
+
+        let mut closes: BTreeMap<NaiveDate, Vec<f64>> = BTreeMap::new();
+        for bar in per_exchange.iter().flatten() {
+            if let (Some(date), Some(close)) = (bar.date, bar.close) {
+                closes.entry(date).or_default().push(close);
+            }
+        }
+
+        Ok(closes
+            .into_iter()
+            .filter_map(|(date, values)| median(&values).map(|m| (date, m)))
+            .collect())
+    }
+}
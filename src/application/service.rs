@@ -1,16 +1,20 @@
+use crate::application::cache::{Cached, ResponseCache};
+use crate::application::catalog_index::{CatalogIndex, FileLookup};
 use crate::domain::{CacheRepository, Content, ContentRepository, ContentType, RepoConfig};
 use base64::{engine::general_purpose, Engine as _};
 use chrono::NaiveDate;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::error;
 
 #[derive(Clone)]
 pub struct ContentService {
     content_repo: Arc<dyn ContentRepository>,
     cache_repo: Arc<dyn CacheRepository>,
+    cache: ResponseCache,
     allowed_repos: Vec<RepoConfig>,
+    catalog: Arc<CatalogIndex>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -35,19 +39,25 @@ impl ContentService {
     pub fn new(
         content_repo: Arc<dyn ContentRepository>,
         cache_repo: Arc<dyn CacheRepository>,
+        cache: ResponseCache,
         allowed_repos: Vec<RepoConfig>,
+        catalog: Arc<CatalogIndex>,
     ) -> Self {
         Self {
             content_repo,
             cache_repo,
+            cache,
             allowed_repos,
+            catalog,
         }
     }
 
-    fn validate_access(&self, source: &str, owner: &str, repo: &str) -> bool {
+    /// Look up the whitelisted configuration for a repository, if any.
+    fn find_repo(&self, source: &str, owner: &str, repo: &str) -> Option<RepoConfig> {
         self.allowed_repos
             .iter()
-            .any(|r| r.source == source && r.owner == owner && r.repo == repo)
+            .find(|r| r.source == source && r.owner == owner && r.repo == repo)
+            .cloned()
     }
 
     /// Check cache health for deep health checks
@@ -66,16 +76,11 @@ impl ContentService {
         repo: String,
         path: String,
         options: AggregateOptions,
-    ) -> anyhow::Result<serde_json::Value> {
-        if !self.validate_access(&source, &owner, &repo) {
+    ) -> anyhow::Result<Cached<serde_json::Value>> {
+        let Some(repo_config) = self.find_repo(&source, &owner, &repo) else {
             anyhow::bail!("Access Denied: This repository path is not whitelisted.");
-        }
-
-        let repo_config = RepoConfig {
-            source: source.clone(),
-            owner: owner.clone(),
-            repo: repo.clone(),
         };
+
         let cache_key = if options.aggregate {
             format!(
                 "v1:gh:{}:{}:{}:{}:agg=true:p{}:l{}",
@@ -85,36 +90,23 @@ impl ContentService {
             format!("v1:gh:{}:{}:{}:{}", source, owner, repo, path)
         };
 
-        // 1. Try Cache
-        if let Ok(Some(cached)) = self.cache_repo.get(&cache_key).await {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&cached) {
-                info!("Cache HIT: {}", cache_key);
-                // Track cache hit metric
-                metrics::counter!("cache_operations_total", "operation" => "hit").increment(1);
-                return Ok(json);
-            }
+        // Day files the catalog index knows to be missing are not fetched
+        if !options.aggregate && self.catalog.lookup_path(&repo_config, &path) == FileLookup::Absent
+        {
+            anyhow::bail!("Not found: {}", path);
         }
 
-        // Track cache miss metric
-        metrics::counter!("cache_operations_total", "operation" => "miss").increment(1);
-
-        // 2. Process - clone repository for static methods
+        // Serve from cache; stale entries are refreshed in the background
         let c_repo = self.content_repo.clone();
-
-        let result = if options.aggregate {
-            Self::process_aggregation(c_repo, repo_config, path, options).await?
-        } else {
-            Self::process_standard(c_repo, repo_config, path).await?
-        };
-
-        // 3. Cache
-        let cache_repo = self.cache_repo.clone();
-        if let Ok(json_str) = serde_json::to_string(&result) {
-            let ttl = 300; // 5 mins
-            let _ = cache_repo.set(&cache_key, &json_str, ttl).await;
-        }
-
-        Ok(result)
+        self.cache
+            .get_or_compute(cache_key, async move {
+                if options.aggregate {
+                    Self::process_aggregation(c_repo, repo_config, path, options).await
+                } else {
+                    Self::process_standard(c_repo, repo_config, path).await
+                }
+            })
+            .await
     }
 
     // Static implementations to decouple from &self lifetime
@@ -167,8 +159,8 @@ impl ContentService {
         path: String,
         opts: AggregateOptions,
     ) -> anyhow::Result<serde_json::Value> {
-        // 1. List files
-        let mut items = content_repo.list_directory(&config, &path).await?;
+        // 1. List files, including those in nested directories (e.g. a whole exchange)
+        let mut items = content_repo.list_tree(&config, &path).await?;
 
         // 2. Filter JSON
         items.retain(|i| i.item_type == ContentType::File && i.name.ends_with(".json"));
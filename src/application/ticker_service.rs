@@ -3,10 +3,18 @@
 //! Provides convenience methods for accessing aggregated token statistics
 //! across all exchanges without requiring directory navigation.
 
-use crate::domain::{CacheRepository, ContentRepository, ContentType, RepoConfig};
-use base64::{engine::general_purpose, Engine as _};
-use chrono::{Duration, NaiveDate, Utc};
+use crate::application::cache::{Cached, ResponseCache};
+use crate::application::catalog_index::{CatalogIndex, FileLookup};
+use crate::application::catalog_service::is_valid_name;
+use crate::application::quote_service::QuoteService;
+use crate::domain::market::MILLIS_THRESHOLD;
+use crate::domain::pricing::{is_outlier, median, MIN_QUOTES_FOR_OUTLIERS};
+use crate::domain::{
+    interval_volumes, ContentRepository, DailySummary, RawTickFile, RepoConfig, Tick,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use futures::StreamExt;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, warn};
@@ -19,8 +27,14 @@ pub struct TickerStatsResponse {
     pub token: String,
     /// Response timestamp (ISO 8601)
     pub timestamp: String,
-    /// Range requested (today, 7d, 30d)
+    /// Range requested (today, 7d, 30d, or `from..to` for custom windows)
     pub range: String,
+    /// First day covered (YYYY-MM-DD)
+    pub from: String,
+    /// Last day covered (YYYY-MM-DD)
+    pub to: String,
+    /// Quote asset all prices and volumes are expressed in
+    pub quote: String,
     /// Per-exchange statistics
     pub exchanges: Vec<ExchangeStats>,
     /// Aggregated statistics across all exchanges
@@ -34,16 +48,27 @@ pub struct ExchangeStats {
     pub exchange: String,
     /// Last trade price
     pub last: Option<f64>,
-    /// 24h high price
+    /// High price (24h for `today`, over the whole range otherwise)
     pub high: Option<f64>,
-    /// 24h low price
+    /// Low price (24h for `today`, over the whole range otherwise)
     pub low: Option<f64>,
-    /// 24h volume (base currency)
+    /// 24h volume (quote currency)
     pub volume_24h: Option<f64>,
-    /// 24h price change percentage
+    /// Price change percentage (24h for `today`, over the whole range otherwise)
     pub change_pct: Option<f64>,
     /// Number of data points in range
     pub data_points: usize,
+    /// Time of the exchange's most recent data point (ISO 8601)
+    pub last_update: Option<String>,
+    /// Whether the most recent data point is older than the staleness limit
+    pub stale: bool,
+    /// Whether `last` deviates from the cross-exchange median beyond the outlier threshold
+    pub outlier: bool,
+    /// Quote asset of the exchange's market, when the data identifies it
+    pub quote: Option<String>,
+    /// Whether prices were converted into the response's `quote`; exchanges that could
+    /// not be are reported in their own quote and left out of `aggregate`
+    pub normalized: bool,
 }
 
 /// Aggregated statistics across all exchanges.
@@ -57,6 +82,35 @@ pub struct AggregateStats {
     pub vwap: Option<f64>,
     /// Number of active exchanges
     pub exchange_count: usize,
+    /// Number of exchanges left out because they are stale or outliers
+    pub excluded_count: usize,
+}
+
+/// Venue flagging settings loaded from the `ticker` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TickerConfig {
+    /// Exchanges whose latest data is older than this are flagged `stale` (default: 3600)
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+    /// Deviation from the median `last` price, in percent, flagged as `outlier` (default: 10)
+    #[serde(default = "default_outlier_threshold_pct")]
+    pub outlier_threshold_pct: f64,
+}
+
+fn default_stale_after_secs() -> u64 {
+    3600
+}
+fn default_outlier_threshold_pct() -> f64 {
+    10.0
+}
+
+impl Default for TickerConfig {
+    fn default() -> Self {
+        Self {
+            stale_after_secs: default_stale_after_secs(),
+            outlier_threshold_pct: default_outlier_threshold_pct(),
+        }
+    }
 }
 
 /// Response structure for ticker history endpoint.
@@ -64,14 +118,50 @@ pub struct AggregateStats {
 pub struct TickerHistoryResponse {
     /// Token symbol/name
     pub token: String,
-    /// Range requested
+    /// Range requested (today, 7d, 30d, or `from..to` for custom windows)
     pub range: String,
+    /// First day covered (YYYY-MM-DD)
+    pub from: String,
+    /// Last day covered (YYYY-MM-DD)
+    pub to: String,
     /// Data resolution
     pub resolution: String,
+    /// Quote asset all prices and volumes are expressed in
+    pub quote: String,
+    /// How candle volumes were derived (see `VOLUME_METHOD_ROLLING_DELTA`
+    /// and `VOLUME_METHOD_DAILY_MEAN`)
+    pub volume_method: String,
+    /// Series layout: `composite` (one cross-exchange series in `data`) or
+    /// `exchange` (one series per exchange in `series`)
+    pub group_by: String,
+    /// Exchanges that contributed data, in alphabetical order
+    pub exchanges: Vec<String>,
+    /// Composite OHLCV data points (empty when grouped by exchange)
+    pub data: Vec<OhlcvPoint>,
+    /// Per-exchange OHLCV series (only when grouped by exchange)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<Vec<ExchangeSeries>>,
+}
+
+/// OHLCV series from a single exchange.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExchangeSeries {
+    /// Exchange identifier
+    pub exchange: String,
+    /// Quote asset of the exchange's market, before conversion
+    pub quote: String,
     /// OHLCV data points
     pub data: Vec<OhlcvPoint>,
 }
 
+/// Intraday candle volume: sum of per-tick estimates from deltas of the exchange's
+/// rolling 24h quote volume, corrected for volume leaving the window. Intervals
+/// spanning collection gaps (> 1h) or counter resets contribute nothing.
+pub const VOLUME_METHOD_ROLLING_DELTA: &str = "rolling_24h_delta";
+
+/// Daily candle volume: mean of the day's rolling 24h quote volume snapshots.
+pub const VOLUME_METHOD_DAILY_MEAN: &str = "daily_mean_rolling_24h";
+
 /// Single OHLCV data point for charting.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OhlcvPoint {
@@ -85,8 +175,11 @@ pub struct OhlcvPoint {
     pub low: f64,
     /// Close price
     pub close: f64,
-    /// Volume
+    /// Quote volume traded in the bucket (see `volume_method`)
     pub volume: f64,
+    /// Number of exchanges combined into a composite candle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sources: Option<usize>,
 }
 
 /// Query parameters for ticker stats endpoint.
@@ -95,6 +188,18 @@ pub struct TickerStatsQuery {
     /// Lookback range: today, 7d, 30d (default: today)
     #[param(default = "today", example = "7d")]
     pub range: Option<String>,
+    /// Window start: YYYY-MM-DD, RFC 3339, or unix timestamp (replaces `range`)
+    #[param(example = "2025-12-01")]
+    pub from: Option<String>,
+    /// Window end, inclusive: same formats as `from` (default: today)
+    #[param(example = "2025-12-31")]
+    pub to: Option<String>,
+    /// Include stale and outlier exchanges in `aggregate` (default: false)
+    #[param(default = false, example = true)]
+    pub include_flagged: Option<bool>,
+    /// Quote asset to express prices in, e.g. USDT, USDC, BTC, KAS (default: USDT)
+    #[param(example = "BTC")]
+    pub quote: Option<String>,
 }
 
 /// Query parameters for ticker history endpoint.
@@ -106,65 +211,319 @@ pub struct TickerHistoryQuery {
     /// Data resolution: 1m, 5m, 1h, 1d (default: 1h)
     #[param(default = "1h", example = "1h")]
     pub resolution: Option<String>,
+    /// Window start: YYYY-MM-DD, RFC 3339, or unix timestamp (replaces `range`)
+    #[param(example = "2025-12-01")]
+    pub from: Option<String>,
+    /// Window end, inclusive: same formats as `from` (default: today)
+    #[param(example = "2025-12-31")]
+    pub to: Option<String>,
+    /// Exchange, or comma-separated list of exchanges, to include (default: up to 5 with data)
+    #[param(example = "ascendex,xeggex")]
+    pub exchange: Option<String>,
+    /// Series layout: composite or exchange (default: composite)
+    #[param(default = "composite", example = "exchange")]
+    pub group_by: Option<String>,
+    /// Quote asset to express prices in, e.g. USDT, USDC, BTC, KAS (default: USDT)
+    #[param(example = "BTC")]
+    pub quote: Option<String>,
+}
+
+/// Most exchanges a history request may name explicitly.
+pub const MAX_HISTORY_EXCHANGES: usize = 10;
+
+/// Exchanges used for a history request when none are named.
+const DEFAULT_HISTORY_EXCHANGES: usize = 5;
+
+/// Exchange directories probed for data when none are named.
+const DEFAULT_HISTORY_TRIES: usize = 15;
+
+/// Raw tick files read to identify the market quote of a daily series.
+const MARKET_QUOTE_TRIES: usize = 3;
+
+/// Layout of the series returned by the history endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryGrouping {
+    /// One cross-exchange series. Candles are built per exchange first, then
+    /// combined per timestamp: open/close are the mean over the exchanges with a
+    /// candle in that bucket, high/low the extremes, volume the sum.
+    Composite,
+    /// One series per exchange, without any cross-venue mixing.
+    Exchange,
+}
+
+impl HistoryGrouping {
+    /// Parse the `group_by` query parameter (default: composite).
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(str::trim) {
+            None | Some("") | Some("composite") => Ok(Self::Composite),
+            Some("exchange") => Ok(Self::Exchange),
+            Some(other) => Err(format!(
+                "Invalid group_by: {}. Use: composite or exchange",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Composite => "composite",
+            Self::Exchange => "exchange",
+        }
+    }
+}
+
+/// Parse the `exchange` query parameter into a sorted, de-duplicated list.
+///
+/// An empty list means "pick exchanges automatically".
+///
+/// # Errors
+///
+/// Returns a client-facing message for names that are not plain directory
+/// names or when more than `MAX_HISTORY_EXCHANGES` are requested.
+pub fn parse_exchange_list(value: Option<&str>) -> Result<Vec<String>, String> {
+    let mut exchanges: Vec<String> = value
+        .unwrap_or_default()
+        .split(',')
+        .map(|e| e.trim().to_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect();
+    exchanges.sort();
+    exchanges.dedup();
+
+    if let Some(bad) = exchanges.iter().find(|e| !is_valid_name(e)) {
+        return Err(format!("Invalid exchange: {}", bad));
+    }
+    if exchanges.len() > MAX_HISTORY_EXCHANGES {
+        return Err(format!(
+            "Too many exchanges: {} (max: {})",
+            exchanges.len(),
+            MAX_HISTORY_EXCHANGES
+        ));
+    }
+    Ok(exchanges)
+}
+
+/// Longest window, in days, accepted by the ticker stats endpoint.
+pub const MAX_STATS_SPAN_DAYS: i64 = 92;
+
+/// Resolutions accepted by the history endpoint.
+pub const HISTORY_RESOLUTIONS: [&str; 4] = ["1m", "5m", "1h", "1d"];
+
+/// Longest window, in days, accepted by the history endpoint at a resolution.
+///
+/// Keeps the number of candles (and raw files fetched) per request bounded.
+pub fn max_history_span_days(resolution: &str) -> i64 {
+    match resolution {
+        "1m" => 3,
+        "5m" => 7,
+        "1h" => 31,
+        _ => 366,
+    }
+}
+
+/// Inclusive date window for a ticker request.
+///
+/// Resolved either from a named `range` relative to today or from explicit
+/// `from`/`to` bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateWindow {
+    /// First day covered
+    pub start: NaiveDate,
+    /// Last day covered
+    pub end: NaiveDate,
+    /// Label echoed in responses and cache keys (`7d`, `2025-12-01..2025-12-31`)
+    pub label: String,
+}
+
+impl DateWindow {
+    /// Resolve query parameters into a window.
+    ///
+    /// `from`/`to` take precedence and cannot be combined with `range`; when only
+    /// `from` is given the window ends today. Without either, `range` (or
+    /// `default_range`) is interpreted relative to today.
+    ///
+    /// # Errors
+    ///
+    /// Returns a client-facing message for unknown ranges, unparseable bounds,
+    /// inverted windows, and windows starting in the future.
+    pub fn resolve(
+        range: Option<&str>,
+        from: Option<&str>,
+        to: Option<&str>,
+        default_range: &str,
+    ) -> Result<Self, String> {
+        let today = Utc::now().date_naive();
+
+        if from.is_none() && to.is_none() {
+            let range = range.unwrap_or(default_range);
+            let days = match range {
+                "today" => 0,
+                "7d" => 7,
+                "30d" => 30,
+                _ => return Err("Invalid range. Use: today, 7d, or 30d".to_string()),
+            };
+            return Ok(Self {
+                start: today - Duration::days(days),
+                end: today,
+                label: range.to_string(),
+            });
+        }
+
+        if range.is_some() {
+            return Err("Use either range or from/to, not both".to_string());
+        }
+        let Some(from) = from else {
+            return Err("Missing from: a window needs a start date".to_string());
+        };
+
+        let start = Self::parse_bound(from)?;
+        let end = match to {
+            Some(to) => Self::parse_bound(to)?,
+            None => today,
+        }
+        .min(today);
+
+        if start > today {
+            return Err(format!("Invalid from: {} is in the future", start));
+        }
+        if start > end {
+            return Err(format!("Invalid window: from {} is after to {}", start, end));
+        }
+
+        Ok(Self {
+            start,
+            end,
+            label: format!("{}..{}", start, end),
+        })
+    }
+
+    /// Number of days covered, inclusive of both ends.
+    pub fn span_days(&self) -> i64 {
+        (self.end - self.start).num_days() + 1
+    }
+
+    /// Parse a window bound: `YYYY-MM-DD`, RFC 3339, or a unix timestamp in
+    /// seconds or milliseconds. Times are reduced to their UTC date.
+    fn parse_bound(value: &str) -> Result<NaiveDate, String> {
+        let value = value.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Ok(date);
+        }
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+            return Ok(dt.with_timezone(&Utc).date_naive());
+        }
+        if let Ok(ts) = value.parse::<i64>() {
+            // Same seconds/milliseconds cut-off as the tick files
+            let secs = if ts.abs() >= MILLIS_THRESHOLD { ts / 1000 } else { ts };
+            if let Some(dt) = chrono::DateTime::from_timestamp(secs, 0) {
+                return Ok(dt.date_naive());
+            }
+        }
+        Err(format!(
+            "Invalid date: {}. Use YYYY-MM-DD, RFC 3339, or a unix timestamp",
+            value
+        ))
+    }
 }
 
 /// Service for ticker-focused operations.
 #[derive(Clone)]
 pub struct TickerService {
     content_repo: Arc<dyn ContentRepository>,
-    cache_repo: Arc<dyn CacheRepository>,
+    cache: ResponseCache,
     default_repo: RepoConfig,
+    settings: TickerConfig,
+    quotes: Arc<QuoteService>,
+    catalog: Arc<CatalogIndex>,
 }
 
 impl TickerService {
     pub fn new(
         content_repo: Arc<dyn ContentRepository>,
-        cache_repo: Arc<dyn CacheRepository>,
+        cache: ResponseCache,
         default_repo: RepoConfig,
+        settings: TickerConfig,
+        quotes: Arc<QuoteService>,
+        catalog: Arc<CatalogIndex>,
     ) -> Self {
         Self {
             content_repo,
-            cache_repo,
+            cache,
             default_repo,
+            settings,
+            quotes,
+            catalog,
         }
     }
 
+    /// Cache key of a stats request.
+    fn stats_cache_key(
+        token: &str,
+        window: &DateWindow,
+        include_flagged: bool,
+        quote: &str,
+    ) -> String {
+        format!(
+            "v1:ticker:{}:stats:{}:{}:{}",
+            token,
+            window.label,
+            quote,
+            if include_flagged { "all" } else { "filtered" }
+        )
+    }
+
     /// Get current stats for a token across all exchanges.
+    ///
+    /// Prices are converted into `quote`. Exchanges flagged `stale` or
+    /// `outlier` are left out of the aggregate unless `include_flagged` is set.
     pub async fn get_ticker_stats(
         &self,
         token: String,
-        range: String,
-    ) -> anyhow::Result<TickerStatsResponse> {
-        let cache_key = format!("v1:ticker:{}:stats:{}", token, range);
-
-        // Check cache first
-        if let Ok(Some(cached)) = self.cache_repo.get(&cache_key).await {
-            if let Ok(response) = serde_json::from_str::<TickerStatsResponse>(&cached) {
-                info!("Cache HIT: {}", cache_key);
-                metrics::counter!("cache_operations_total", "operation" => "hit").increment(1);
-                return Ok(response);
-            }
-        }
-        metrics::counter!("cache_operations_total", "operation" => "miss").increment(1);
+        window: DateWindow,
+        include_flagged: bool,
+        quote: String,
+    ) -> anyhow::Result<Cached<TickerStatsResponse>> {
+        let cache_key = Self::stats_cache_key(&token, &window, include_flagged, &quote);
+        let this = self.clone();
+        self.cache
+            .get_or_compute(cache_key, async move {
+                this.build_ticker_stats(token, window, include_flagged, quote)
+                    .await
+            })
+            .await
+    }
 
-        // Discover exchanges for this token
-        let token_path = format!("data/{}", token.to_lowercase());
-        let exchanges = self
-            .content_repo
-            .list_directory(&self.default_repo, &token_path)
+    /// Compute ticker stats without reading the cache, and cache the result.
+    pub async fn refresh_ticker_stats(
+        &self,
+        token: String,
+        window: DateWindow,
+        include_flagged: bool,
+        quote: String,
+    ) -> anyhow::Result<TickerStatsResponse> {
+        let cache_key = Self::stats_cache_key(&token, &window, include_flagged, &quote);
+        let response = self
+            .build_ticker_stats(token, window, include_flagged, quote)
             .await?;
+        self.cache.store(&cache_key, &response).await;
+        Ok(response)
+    }
 
-        let exchange_dirs: Vec<_> = exchanges
-            .into_iter()
-            .filter(|e| e.item_type == ContentType::Dir)
-            .collect();
+    async fn build_ticker_stats(
+        &self,
+        token: String,
+        window: DateWindow,
+        include_flagged: bool,
+        quote: String,
+    ) -> anyhow::Result<TickerStatsResponse> {
+        // Discover exchanges for this token
+        let exchange_dirs = self.catalog.list_exchanges(&token).await?;
 
         if exchange_dirs.is_empty() {
             anyhow::bail!("No exchanges found for token: {}", token);
         }
 
-        // Calculate date range
-        let (start_date, end_date) = Self::calculate_date_range(&range);
+        let (start_date, end_date) = (window.start, window.end);
 
         // Fetch stats from each exchange concurrently
         let mut exchange_stats = Vec::new();
@@ -172,11 +531,13 @@ impl TickerService {
             .map(|exchange| {
                 let repo = self.content_repo.clone();
                 let config = self.default_repo.clone();
+                let catalog = self.catalog.clone();
                 let token = token.clone();
                 let start = start_date;
                 let end = end_date;
                 async move {
-                    Self::fetch_exchange_stats(repo, config, token, exchange.name, start, end).await
+                    Self::fetch_exchange_stats(repo, config, catalog, token, exchange, start, end)
+                        .await
                 }
             })
             .buffer_unordered(10)
@@ -190,166 +551,410 @@ impl TickerService {
             }
         }
 
-        // Calculate aggregate stats
-        let aggregate = Self::calculate_aggregate(&exchange_stats);
+        // Bring every venue into the requested quote, flag stale venues and
+        // bad prints, then aggregate
+        self.normalize_stats(&mut exchange_stats, &quote, &window)
+            .await;
+        self.flag_exchanges(&mut exchange_stats, &window);
+        let aggregate = Self::calculate_aggregate(&exchange_stats, include_flagged);
 
         let response = TickerStatsResponse {
             token: token.clone(),
             timestamp: Utc::now().to_rfc3339(),
-            range: range.clone(),
+            range: window.label.clone(),
+            from: window.start.to_string(),
+            to: window.end.to_string(),
+            quote,
             exchanges: exchange_stats,
             aggregate,
         };
 
-        // Cache result (5 min TTL)
-        if let Ok(json) = serde_json::to_string(&response) {
-            let _ = self.cache_repo.set(&cache_key, &json, 300).await;
-        }
-
         Ok(response)
     }
 
+    /// Cache key of a history request.
+    fn history_cache_key(
+        token: &str,
+        window: &DateWindow,
+        resolution: &str,
+        exchanges: &[String],
+        grouping: HistoryGrouping,
+        quote: &str,
+    ) -> String {
+        let selection = if exchanges.is_empty() {
+            "auto".to_string()
+        } else {
+            exchanges.join(",")
+        };
+        format!(
+            "v1:ticker:{}:history:{}:{}:{}:{}:{}",
+            token,
+            window.label,
+            resolution,
+            quote,
+            selection,
+            grouping.as_str()
+        )
+    }
+
     /// Get historical data for a token (for charting).
+    ///
+    /// `exchanges` restricts the request to the named exchanges; when empty, up
+    /// to `DEFAULT_HISTORY_EXCHANGES` exchanges with data are picked. Candles are
+    /// converted into `quote` at each day's rate; candles without a rate are dropped.
     pub async fn get_ticker_history(
         &self,
         token: String,
-        range: String,
+        window: DateWindow,
         resolution: String,
-    ) -> anyhow::Result<TickerHistoryResponse> {
-        let cache_key = format!("v1:ticker:{}:history:{}:{}", token, range, resolution);
-
-        // Check cache first
-        if let Ok(Some(cached)) = self.cache_repo.get(&cache_key).await {
-            if let Ok(response) = serde_json::from_str::<TickerHistoryResponse>(&cached) {
-                info!("Cache HIT: {}", cache_key);
-                metrics::counter!("cache_operations_total", "operation" => "hit").increment(1);
-                return Ok(response);
-            }
-        }
-        metrics::counter!("cache_operations_total", "operation" => "miss").increment(1);
+        exchanges: Vec<String>,
+        grouping: HistoryGrouping,
+        quote: String,
+    ) -> anyhow::Result<Cached<TickerHistoryResponse>> {
+        let cache_key =
+            Self::history_cache_key(&token, &window, &resolution, &exchanges, grouping, &quote);
+        let this = self.clone();
+        self.cache
+            .get_or_compute(cache_key, async move {
+                this.build_ticker_history(token, window, resolution, exchanges, grouping, quote)
+                    .await
+            })
+            .await
+    }
 
-        // Discover exchanges for this token
-        let token_path = format!("data/{}", token.to_lowercase());
-        let exchanges = self
-            .content_repo
-            .list_directory(&self.default_repo, &token_path)
+    /// Compute ticker history without reading the cache, and cache the result.
+    pub async fn refresh_ticker_history(
+        &self,
+        token: String,
+        window: DateWindow,
+        resolution: String,
+        exchanges: Vec<String>,
+        grouping: HistoryGrouping,
+        quote: String,
+    ) -> anyhow::Result<TickerHistoryResponse> {
+        let cache_key =
+            Self::history_cache_key(&token, &window, &resolution, &exchanges, grouping, &quote);
+        let response = self
+            .build_ticker_history(token, window, resolution, exchanges, grouping, quote)
             .await?;
+        self.cache.store(&cache_key, &response).await;
+        Ok(response)
+    }
 
-        let exchange_dirs: Vec<_> = exchanges
-            .into_iter()
-            .filter(|e| e.item_type == ContentType::Dir)
-            .collect();
+    async fn build_ticker_history(
+        &self,
+        token: String,
+        window: DateWindow,
+        resolution: String,
+        exchanges: Vec<String>,
+        grouping: HistoryGrouping,
+        quote: String,
+    ) -> anyhow::Result<TickerHistoryResponse> {
+        // Discover exchanges for this token
+        let exchange_dirs = self.catalog.list_exchanges(&token).await?;
 
         if exchange_dirs.is_empty() {
             anyhow::bail!("No exchanges found for token: {}", token);
         }
 
-        let (start_date, end_date) = Self::calculate_date_range(&range);
+        // Named exchanges must all exist; otherwise try up to 15 to find ones with data
+        let (candidates, wanted) = if exchanges.is_empty() {
+            let candidates: Vec<String> = exchange_dirs
+                .into_iter()
+                .take(DEFAULT_HISTORY_TRIES)
+                .collect();
+            (candidates, DEFAULT_HISTORY_EXCHANGES)
+        } else {
+            let missing: Vec<&str> = exchanges
+                .iter()
+                .filter(|e| !exchange_dirs.contains(e))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                anyhow::bail!("Exchange not found for {}: {}", token, missing.join(", "));
+            }
+            let wanted = exchanges.len();
+            (exchanges, wanted)
+        };
 
-        // Collect raw data from exchanges - try up to 10 to find ones with data
-        let mut all_data: Vec<serde_json::Value> = Vec::new();
-        let mut exchanges_with_data = 0;
-        const MAX_EXCHANGES: usize = 5;
-        const MAX_TRIES: usize = 15;
+        let daily = resolution == "1d";
+        let mut series: Vec<ExchangeSeries> = Vec::new();
 
-        for exchange in exchange_dirs.iter().take(MAX_TRIES) {
-            if exchanges_with_data >= MAX_EXCHANGES {
+        for exchange in candidates {
+            if series.len() >= wanted {
                 break;
             }
-            
-            match Self::fetch_exchange_raw_data(
-                self.content_repo.clone(),
-                self.default_repo.clone(),
-                token.clone(),
-                exchange.name.clone(),
-                start_date,
-                end_date,
-            )
-            .await
-            {
-                Ok(data) => {
-                    if !data.is_empty() {
-                        info!("Found {} data points from {} for history", data.len(), exchange.name);
-                        all_data.extend(data);
-                        exchanges_with_data += 1;
-                    }
-                }
-                Err(e) => warn!("Failed to fetch data from {}: {}", exchange.name, e),
+            let (market_quote, data) = self
+                .fetch_exchange_candles(&token, &exchange, &window, &resolution)
+                .await;
+            if data.is_empty() {
+                continue;
             }
+            // Candles in an unknown quote cannot be converted or merged
+            let Some(market_quote) = market_quote else {
+                warn!("Unknown quote for {} on {}, skipped", token, exchange);
+                continue;
+            };
+            info!("Found {} candles from {} for history", data.len(), exchange);
+            series.push(ExchangeSeries {
+                exchange,
+                quote: market_quote,
+                data,
+            });
         }
 
-        // Aggregate into OHLCV based on resolution
-        let ohlcv_data = Self::aggregate_to_ohlcv(&all_data, &resolution);
+        // Bring every series into the requested quote
+        let mut assets: Vec<String> = series.iter().map(|s| s.quote.clone()).collect();
+        assets.push(quote.clone());
+        assets.sort();
+        assets.dedup();
+        let rates = self.quotes.load_rates(&assets, &window).await;
+        for s in series.iter_mut() {
+            let total = s.data.len();
+            s.data.retain_mut(|candle| {
+                let date = DateTime::from_timestamp(candle.timestamp, 0)
+                    .map(|t| t.date_naive())
+                    .unwrap_or(window.end);
+                let Some(factor) = rates.factor(&s.quote, &quote, date) else {
+                    return false;
+                };
+                candle.open *= factor;
+                candle.high *= factor;
+                candle.low *= factor;
+                candle.close *= factor;
+                candle.volume *= factor;
+                true
+            });
+            if s.data.len() < total {
+                warn!(
+                    "Dropped {} of {} {} candles from {}: no {}/{} rate",
+                    total - s.data.len(),
+                    total,
+                    token,
+                    s.exchange,
+                    s.quote,
+                    quote
+                );
+            }
+        }
+        series.retain(|s| !s.data.is_empty());
+        series.sort_by(|a, b| a.exchange.cmp(&b.exchange));
+
+        let volume_method = if daily {
+            VOLUME_METHOD_DAILY_MEAN
+        } else {
+            VOLUME_METHOD_ROLLING_DELTA
+        };
+        let exchanges = series.iter().map(|s| s.exchange.clone()).collect();
+        let (data, series) = match grouping {
+            HistoryGrouping::Composite => {
+                let candles = series.into_iter().flat_map(|s| s.data).collect();
+                (Self::merge_candles(candles), None)
+            }
+            HistoryGrouping::Exchange => (Vec::new(), Some(series)),
+        };
 
         let response = TickerHistoryResponse {
             token: token.clone(),
-            range: range.clone(),
+            range: window.label.clone(),
+            from: window.start.to_string(),
+            to: window.end.to_string(),
             resolution: resolution.clone(),
-            data: ohlcv_data,
+            quote,
+            volume_method: volume_method.to_string(),
+            group_by: grouping.as_str().to_string(),
+            exchanges,
+            data,
+            series,
         };
 
-        // Cache result (5 min TTL)
-        if let Ok(json) = serde_json::to_string(&response) {
-            let _ = self.cache_repo.set(&cache_key, &json, 300).await;
+        Ok(response)
+    }
+
+    /// Build one exchange's candles over the window, with the market's quote asset
+    /// when the data identifies it.
+    ///
+    /// Daily candles come from the precomputed summary files; finer resolutions
+    /// are rebuilt from raw ticks. Returns an empty series when there is no data.
+    async fn fetch_exchange_candles(
+        &self,
+        token: &str,
+        exchange: &str,
+        window: &DateWindow,
+        resolution: &str,
+    ) -> (Option<String>, Vec<OhlcvPoint>) {
+        if resolution == "1d" {
+            let bars = Self::fetch_daily_bars(
+                &self.content_repo,
+                &self.default_repo,
+                &self.catalog,
+                token,
+                exchange,
+                window.start,
+                window.end,
+            )
+            .await;
+            let dates = Self::recent_days(window.start, window.end);
+            let quote = Self::market_quote(
+                &self.content_repo,
+                &self.default_repo,
+                &self.catalog,
+                token,
+                exchange,
+                &dates,
+            )
+            .await;
+            return (
+                quote,
+                bars.iter().filter_map(Self::summary_to_candle).collect(),
+            );
         }
 
-        Ok(response)
+        match Self::fetch_exchange_raw_data(
+            self.content_repo.clone(),
+            self.default_repo.clone(),
+            self.catalog.clone(),
+            token.to_string(),
+            exchange.to_string(),
+            window.start,
+            window.end,
+        )
+        .await
+        {
+            // Volumes are derived per market, so each exchange is bucketed separately
+            Ok(ticks) => (
+                ticks.iter().rev().find_map(Tick::quote_asset),
+                Self::aggregate_to_ohlcv(&ticks, resolution),
+            ),
+            Err(e) => {
+                warn!("Failed to fetch data from {}: {}", exchange, e);
+                (None, Vec::new())
+            }
+        }
     }
 
-    fn calculate_date_range(range: &str) -> (NaiveDate, NaiveDate) {
-        let today = Utc::now().date_naive();
-        let start = match range {
-            "today" => today,
-            "7d" => today - Duration::days(7),
-            "30d" => today - Duration::days(30),
-            _ => today,
-        };
-        (start, today)
+    /// Repository path of a day's file for a token on an exchange.
+    ///
+    /// `suffix` is `"-raw"` for tick files and `""` for daily summaries.
+    fn day_path(token: &str, exchange: &str, date: NaiveDate, suffix: &str) -> String {
+        format!(
+            "data/{}/{}/{}/{}/{}{}.json",
+            token.to_lowercase(),
+            exchange,
+            date.format("%Y"),
+            date.format("%m"),
+            date.format("%Y-%m-%d"),
+            suffix
+        )
+    }
+
+    /// Fetch a repository file and deserialize its JSON body.
+    ///
+    /// Files the catalog index knows to be missing are not requested.
+    async fn fetch_json<T: DeserializeOwned>(
+        repo: &Arc<dyn ContentRepository>,
+        config: &RepoConfig,
+        catalog: &CatalogIndex,
+        path: &str,
+    ) -> anyhow::Result<T> {
+        if catalog.lookup_path(config, path) == FileLookup::Absent {
+            anyhow::bail!("Not found: {}", path);
+        }
+        let content = repo.get_content(config, path).await?;
+        let bytes = content.decode()?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Fetch and validate one day of raw ticks for an exchange.
+    async fn fetch_raw_ticks(
+        repo: &Arc<dyn ContentRepository>,
+        config: &RepoConfig,
+        catalog: &CatalogIndex,
+        token: &str,
+        exchange: &str,
+        date: NaiveDate,
+    ) -> anyhow::Result<RawTickFile> {
+        let path = Self::day_path(token, exchange, date, "-raw");
+        let file: RawTickFile = Self::fetch_json(repo, config, catalog, &path).await?;
+
+        let report = &file.report;
+        if !report.is_clean() {
+            warn!(
+                "Repaired tick file {}: dropped {} of {} records (malformed: {}, no timestamp: {}, no price: {}, duplicates: {}), reordered: {}, declared count: {:?}",
+                path,
+                report.rejected(),
+                report.total,
+                report.malformed,
+                report.missing_timestamp,
+                report.missing_price,
+                report.duplicates,
+                report.reordered,
+                report.declared_count
+            );
+            metrics::counter!("tick_records_rejected_total", "exchange" => exchange.to_string())
+                .increment(report.rejected() as u64);
+        }
+        metrics::counter!("tick_records_accepted_total", "exchange" => exchange.to_string())
+            .increment(report.accepted as u64);
+
+        Ok(file)
     }
 
     async fn fetch_exchange_stats(
         repo: Arc<dyn ContentRepository>,
         config: RepoConfig,
+        catalog: Arc<CatalogIndex>,
         token: String,
         exchange: String,
-        _start_date: NaiveDate,
-        _end_date: NaiveDate,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
     ) -> anyhow::Result<ExchangeStats> {
-        // Try to get data file - try today first, then fall back to previous days
-        let today = Utc::now().date_naive();
-        let days_to_try = [today, today - Duration::days(1), today - Duration::days(2)];
+        // Multi-day ranges are served from the daily summary files
+        if start_date < end_date {
+            let bars = Self::fetch_daily_bars(
+                &repo, &config, &catalog, &token, &exchange, start_date, end_date,
+            )
+            .await;
+            let dates = Self::recent_days(start_date, end_date);
+            let quote =
+                Self::market_quote(&repo, &config, &catalog, &token, &exchange, &dates).await;
+            return Ok(Self::range_stats(&exchange, &bars, quote));
+        }
 
-        for date in days_to_try {
-            let year = date.format("%Y");
-            let month = date.format("%m");
-            let date_path = format!(
-                "data/{}/{}/{}/{}/{}-raw.json",
-                token.to_lowercase(),
-                exchange,
-                year,
-                month,
-                date.format("%Y-%m-%d")
-            );
+        // Try the window's day first, then fall back to the two days before it
+        let days_to_try = [
+            end_date,
+            end_date - Duration::days(1),
+            end_date - Duration::days(2),
+        ];
 
-            // Try to fetch the file
-            match repo.get_content(&config, &date_path).await {
-                Ok(content) => {
-                    // Parse the content
-                    if let (Some(raw), Some(enc)) = (content.content, content.encoding) {
-                        if enc == "base64" {
-                            let clean = raw.replace('\n', "");
-                            if let Ok(bytes) = general_purpose::STANDARD.decode(&clean) {
-                                if let Ok(s) = String::from_utf8(bytes) {
-                                    if let Ok(json) = serde_json::from_str::<serde_json::Value>(&s) {
-                                        info!("Found data for {} from {} for date {}", token, exchange, date);
-                                        return Self::parse_exchange_stats(&exchange, &json);
-                                    }
-                                }
-                            }
+        for date in days_to_try {
+            match Self::fetch_raw_ticks(&repo, &config, &catalog, &token, &exchange, date).await {
+                Ok(file) if !file.ticks.is_empty() => {
+                    info!("Found data for {} from {} for date {}", token, exchange, date);
+                    return Ok(Self::parse_exchange_stats(&exchange, &file.ticks));
+                }
+                _ => {
+                    // No usable ticks - the daily summary may still have the day's prices
+                    let summary_path = Self::day_path(&token, &exchange, date, "");
+                    if let Ok(summary) =
+                        Self::fetch_json::<DailySummary>(&repo, &config, &catalog, &summary_path)
+                            .await
+                    {
+                        if summary.has_prices() {
+                            info!("Using daily summary for {} from {} for date {}", token, exchange, date);
+                            let quote = Self::market_quote(
+                                &repo,
+                                &config,
+                                &catalog,
+                                &token,
+                                &exchange,
+                                &days_to_try,
+                            )
+                            .await;
+                            return Ok(Self::summary_stats(&exchange, &summary, quote));
                         }
                     }
-                }
-                Err(_) => {
                     // Try next day
                     continue;
                 }
@@ -357,83 +962,290 @@ impl TickerService {
         }
 
         // Return empty stats if no data found in any of the days
-        Ok(ExchangeStats {
-            exchange,
+        Ok(Self::empty_stats(&exchange))
+    }
+
+    fn empty_stats(exchange: &str) -> ExchangeStats {
+        ExchangeStats {
+            exchange: exchange.to_string(),
             last: None,
             high: None,
             low: None,
             volume_24h: None,
             change_pct: None,
             data_points: 0,
-        })
+            last_update: None,
+            stale: false,
+            outlier: false,
+            quote: None,
+            normalized: false,
+        }
     }
 
-    fn parse_exchange_stats(
+    /// Fetch one `DailySummary` per day in `[start_date, end_date]` for an exchange.
+    ///
+    /// Each day is read from its `YYYY-MM-DD.json` summary file. Only days whose
+    /// summary is missing or has no prices fall back to downloading the
+    /// `-raw.json` tick file and rebuilding the summary from it. Days with no
+    /// data at all are omitted. Results are in ascending date order.
+    pub(crate) async fn fetch_daily_bars(
+        repo: &Arc<dyn ContentRepository>,
+        config: &RepoConfig,
+        catalog: &CatalogIndex,
+        token: &str,
         exchange: &str,
-        json: &serde_json::Value,
-    ) -> anyhow::Result<ExchangeStats> {
-        let data = json.get("data").and_then(|d| d.as_array());
-
-        if let Some(arr) = data {
-            if arr.is_empty() {
-                return Ok(ExchangeStats {
-                    exchange: exchange.to_string(),
-                    last: None,
-                    high: None,
-                    low: None,
-                    volume_24h: None,
-                    change_pct: None,
-                    data_points: 0,
-                });
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Vec<DailySummary> {
+        let days: Vec<NaiveDate> = start_date
+            .iter_days()
+            .take_while(|d| *d <= end_date)
+            .collect();
+
+        let mut bars: Vec<DailySummary> = futures::stream::iter(days)
+            .map(|date| async move {
+                let summary_path = Self::day_path(token, exchange, date, "");
+                if let Ok(summary) =
+                    Self::fetch_json::<DailySummary>(repo, config, catalog, &summary_path).await
+                {
+                    if summary.has_prices() {
+                        metrics::counter!("ticker_daily_source_total", "source" => "summary")
+                            .increment(1);
+                        return Some(DailySummary {
+                            date: summary.date.or(Some(date)),
+                            ..summary
+                        });
+                    }
+                }
+
+                let file = Self::fetch_raw_ticks(repo, config, catalog, token, exchange, date)
+                    .await
+                    .ok()?;
+                let rebuilt = DailySummary::from_ticks(date, &file.ticks)?;
+                metrics::counter!("ticker_daily_source_total", "source" => "raw").increment(1);
+                Some(rebuilt)
+            })
+            .buffer_unordered(10)
+            .filter_map(|bar| async move { bar })
+            .collect()
+            .await;
+
+        bars.sort_by_key(|b| b.date);
+        bars
+    }
+
+    /// The last `MARKET_QUOTE_TRIES` days of `[start_date, end_date]`, newest first.
+    pub(crate) fn recent_days(start_date: NaiveDate, end_date: NaiveDate) -> Vec<NaiveDate> {
+        (0..MARKET_QUOTE_TRIES as i64)
+            .map(|i| end_date - Duration::days(i))
+            .take_while(|d| *d >= start_date)
+            .collect()
+    }
+
+    /// Quote asset of an exchange's market, from the first of `dates` whose raw
+    /// tick file identifies it.
+    ///
+    /// Daily summaries do not name their market, so the daily paths read the
+    /// quote from the raw files.
+    pub(crate) async fn market_quote(
+        repo: &Arc<dyn ContentRepository>,
+        config: &RepoConfig,
+        catalog: &CatalogIndex,
+        token: &str,
+        exchange: &str,
+        dates: &[NaiveDate],
+    ) -> Option<String> {
+        for &date in dates {
+            let Ok(file) =
+                Self::fetch_raw_ticks(repo, config, catalog, token, exchange, date).await
+            else {
+                continue;
+            };
+            if let Some(quote) = file.ticks.iter().rev().find_map(Tick::quote_asset) {
+                return Some(quote);
             }
+        }
+        None
+    }
 
-            // Get latest data point
-            let latest = &arr[arr.len() - 1];
+    /// Statistics over a multi-day range built from daily bars.
+    ///
+    /// `high`/`low` span the whole range, `change_pct` compares the last close
+    /// with the first open, and `volume_24h` is the most recent day's estimated volume.
+    fn range_stats(exchange: &str, bars: &[DailySummary], quote: Option<String>) -> ExchangeStats {
+        let (Some(first), Some(latest)) = (bars.first(), bars.last()) else {
+            return Self::empty_stats(exchange);
+        };
 
-            // Calculate high/low across all data points
-            let mut high: Option<f64> = None;
-            let mut low: Option<f64> = None;
-            let mut total_volume: f64 = 0.0;
+        let change_pct = match (first.open, latest.close) {
+            (Some(open), Some(close)) if open > 0.0 => Some((close - open) / open * 100.0),
+            _ => None,
+        };
 
-            for point in arr {
-                if let Some(h) = point.get("high").and_then(|v| v.as_f64()) {
-                    high = Some(high.map_or(h, |curr| curr.max(h)));
-                }
-                if let Some(l) = point.get("low").and_then(|v| v.as_f64()) {
-                    low = Some(low.map_or(l, |curr| curr.min(l)));
-                }
-                if let Some(v) = point.get("quoteVolume").and_then(|v| v.as_f64()) {
-                    total_volume = v; // Use latest quoteVolume as it's cumulative
+        ExchangeStats {
+            exchange: exchange.to_string(),
+            last: latest.close,
+            high: bars.iter().filter_map(|b| b.high).reduce(f64::max),
+            low: bars.iter().filter_map(|b| b.low).reduce(f64::min),
+            volume_24h: latest.estimated_volume(),
+            change_pct,
+            data_points: bars.iter().filter_map(|b| b.trades).sum::<u64>() as usize,
+            last_update: Self::format_update(latest.last_update),
+            stale: false,
+            outlier: false,
+            quote,
+            normalized: false,
+        }
+    }
+
+    fn summary_stats(
+        exchange: &str,
+        summary: &DailySummary,
+        quote: Option<String>,
+    ) -> ExchangeStats {
+        ExchangeStats {
+            exchange: exchange.to_string(),
+            last: summary.close,
+            high: summary.high,
+            low: summary.low,
+            volume_24h: summary.estimated_volume(),
+            change_pct: summary.percentage,
+            data_points: summary.trades.unwrap_or(0) as usize,
+            last_update: Self::format_update(summary.last_update),
+            stale: false,
+            outlier: false,
+            quote,
+            normalized: false,
+        }
+    }
+
+    fn parse_exchange_stats(exchange: &str, ticks: &[Tick]) -> ExchangeStats {
+        // Get latest data point
+        let Some(latest) = ticks.last() else {
+            return Self::empty_stats(exchange);
+        };
+
+        // Calculate high/low across all data points
+        let high = ticks
+            .iter()
+            .filter_map(|t| t.high.or(t.price()))
+            .reduce(f64::max);
+        let low = ticks
+            .iter()
+            .filter_map(|t| t.low.or(t.price()))
+            .reduce(f64::min);
+        // Use latest quoteVolume as it's the exchange's rolling 24h figure
+        let volume = ticks.iter().rev().find_map(|t| t.quote_volume);
+
+        ExchangeStats {
+            exchange: exchange.to_string(),
+            last: latest.price(),
+            high,
+            low,
+            volume_24h: volume,
+            change_pct: latest.percentage,
+            data_points: ticks.len(),
+            last_update: Self::format_update(Some(latest.timestamp)),
+            stale: false,
+            outlier: false,
+            quote: ticks.iter().rev().find_map(Tick::quote_asset),
+            normalized: false,
+        }
+    }
+
+    /// Render a Unix millisecond timestamp as ISO 8601.
+    fn format_update(timestamp_ms: Option<i64>) -> Option<String> {
+        timestamp_ms
+            .and_then(DateTime::from_timestamp_millis)
+            .map(|t| t.to_rfc3339())
+    }
+
+    /// Convert each exchange's prices and volume into `quote`.
+    ///
+    /// Markets whose quote is unknown cannot be converted and are left
+    /// unnormalized. Values are converted at the rate of the window's last day.
+    async fn normalize_stats(
+        &self,
+        exchanges: &mut [ExchangeStats],
+        quote: &str,
+        window: &DateWindow,
+    ) {
+        let mut assets: Vec<String> = exchanges.iter().filter_map(|e| e.quote.clone()).collect();
+        assets.push(quote.to_string());
+        assets.sort();
+        assets.dedup();
+        let rates = self.quotes.load_rates(&assets, window).await;
+
+        for e in exchanges.iter_mut() {
+            let Some(market_quote) = e.quote.as_deref() else {
+                if e.last.is_some() {
+                    warn!("Unknown quote for {}, left unnormalized", e.exchange);
                 }
-            }
+                continue;
+            };
+            let Some(factor) = rates.factor(market_quote, quote, window.end) else {
+                warn!("No {}/{} rate for {}", market_quote, quote, e.exchange);
+                continue;
+            };
+            e.last = e.last.map(|v| v * factor);
+            e.high = e.high.map(|v| v * factor);
+            e.low = e.low.map(|v| v * factor);
+            e.volume_24h = e.volume_24h.map(|v| v * factor);
+            e.normalized = true;
+        }
+    }
 
-            Ok(ExchangeStats {
-                exchange: exchange.to_string(),
-                last: latest.get("last").and_then(|v| v.as_f64()),
-                high,
-                low,
-                volume_24h: Some(total_volume),
-                change_pct: latest.get("percentage").and_then(|v| v.as_f64()),
-                data_points: arr.len(),
-            })
-        } else {
-            Ok(ExchangeStats {
-                exchange: exchange.to_string(),
-                last: None,
-                high: None,
-                low: None,
-                volume_24h: None,
-                change_pct: None,
-                data_points: 0,
-            })
+    /// Set the `stale` and `outlier` flags on each exchange.
+    ///
+    /// Staleness is measured against the end of the window (or now, for windows
+    /// ending today), so historical windows are not flagged wholesale; exchanges
+    /// without any data are always stale. Outliers are judged against the median
+    /// `last` of the fresh exchanges, once there are enough of them.
+    fn flag_exchanges(&self, exchanges: &mut [ExchangeStats], window: &DateWindow) {
+        let window_end = (window.end + Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .map(|t| t.and_utc())
+            .unwrap_or_else(Utc::now);
+        let reference = window_end.min(Utc::now());
+        let stale_after = Duration::seconds(self.settings.stale_after_secs as i64);
+
+        for e in exchanges.iter_mut() {
+            let updated = e
+                .last_update
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+            e.stale = match updated {
+                Some(t) => reference.signed_duration_since(t) > stale_after,
+                None => true,
+            };
+        }
+
+        let fresh_prices: Vec<f64> = exchanges
+            .iter()
+            .filter(|e| !e.stale && e.normalized)
+            .filter_map(|e| e.last)
+            .collect();
+        if fresh_prices.len() < MIN_QUOTES_FOR_OUTLIERS {
+            return;
+        }
+        let Some(reference_price) = median(&fresh_prices) else {
+            return;
+        };
+        for e in exchanges.iter_mut().filter(|e| e.normalized) {
+            e.outlier = e.last.is_some_and(|p| {
+                is_outlier(p, reference_price, self.settings.outlier_threshold_pct)
+            });
         }
     }
 
-    fn calculate_aggregate(exchanges: &[ExchangeStats]) -> AggregateStats {
-        let active_exchanges: Vec<_> = exchanges
+    fn calculate_aggregate(exchanges: &[ExchangeStats], include_flagged: bool) -> AggregateStats {
+        let priced: Vec<_> = exchanges.iter().filter(|e| e.last.is_some()).collect();
+        let active_exchanges: Vec<_> = priced
             .iter()
-            .filter(|e| e.last.is_some())
+            .copied()
+            .filter(|e| e.normalized && (include_flagged || !(e.stale || e.outlier)))
             .collect();
+        let excluded_count = priced.len() - active_exchanges.len();
 
         if active_exchanges.is_empty() {
             return AggregateStats {
@@ -441,6 +1253,7 @@ impl TickerService {
                 total_volume_24h: None,
                 vwap: None,
                 exchange_count: 0,
+                excluded_count,
             };
         }
 
@@ -475,58 +1288,80 @@ impl TickerService {
             total_volume_24h: Some(total_volume),
             vwap,
             exchange_count: active_exchanges.len(),
+            excluded_count,
         }
     }
 
-    async fn fetch_exchange_raw_data(
+    pub(crate) async fn fetch_exchange_raw_data(
         repo: Arc<dyn ContentRepository>,
         config: RepoConfig,
+        catalog: Arc<CatalogIndex>,
         token: String,
         exchange: String,
         start_date: NaiveDate,
         end_date: NaiveDate,
-    ) -> anyhow::Result<Vec<serde_json::Value>> {
-        let mut all_data = Vec::new();
+    ) -> anyhow::Result<Vec<Tick>> {
+        let mut all_ticks = Vec::new();
         let mut current = start_date;
 
         while current <= end_date {
-            let year = current.format("%Y");
-            let month = current.format("%m");
-            let date_path = format!(
-                "data/{}/{}/{}/{}/{}-raw.json",
-                token.to_lowercase(),
-                exchange,
-                year,
-                month,
-                current.format("%Y-%m-%d")
-            );
-
-            if let Ok(content) = repo.get_content(&config, &date_path).await {
-                if let (Some(raw), Some(enc)) = (content.content, content.encoding) {
-                    if enc == "base64" {
-                        let clean = raw.replace('\n', "");
-                        if let Ok(bytes) = general_purpose::STANDARD.decode(&clean) {
-                            if let Ok(s) = String::from_utf8(bytes) {
-                                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&s) {
-                                    if let Some(data) = json.get("data").and_then(|d| d.as_array())
-                                    {
-                                        all_data.extend(data.clone());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+            if let Ok(file) =
+                Self::fetch_raw_ticks(&repo, &config, &catalog, &token, &exchange, current).await
+            {
+                all_ticks.extend(file.ticks);
             }
 
             current += Duration::days(1);
         }
 
-        Ok(all_data)
+        Ok(all_ticks)
+    }
+
+    /// Convert a daily bar into a candle stamped at midnight UTC.
+    fn summary_to_candle(bar: &DailySummary) -> Option<OhlcvPoint> {
+        let close = bar.close?;
+        let timestamp = bar.date?.and_hms_opt(0, 0, 0)?.and_utc().timestamp();
+        Some(OhlcvPoint {
+            timestamp,
+            open: bar.open.unwrap_or(close),
+            high: bar.high.unwrap_or(close),
+            low: bar.low.unwrap_or(close),
+            close,
+            volume: bar.estimated_volume().unwrap_or(0.0),
+            sources: None,
+        })
+    }
+
+    /// Combine candles from several exchanges that share a timestamp.
+    ///
+    /// Open and close are averaged, high/low take the extremes and volume is summed.
+    /// `sources` records how many exchanges contributed to each candle.
+    fn merge_candles(candles: Vec<OhlcvPoint>) -> Vec<OhlcvPoint> {
+        let mut buckets: std::collections::BTreeMap<i64, Vec<OhlcvPoint>> =
+            std::collections::BTreeMap::new();
+        for candle in candles {
+            buckets.entry(candle.timestamp).or_default().push(candle);
+        }
+
+        buckets
+            .into_iter()
+            .map(|(timestamp, group)| {
+                let n = group.len() as f64;
+                OhlcvPoint {
+                    timestamp,
+                    open: group.iter().map(|c| c.open).sum::<f64>() / n,
+                    high: group.iter().map(|c| c.high).fold(f64::MIN, f64::max),
+                    low: group.iter().map(|c| c.low).fold(f64::MAX, f64::min),
+                    close: group.iter().map(|c| c.close).sum::<f64>() / n,
+                    volume: group.iter().map(|c| c.volume).sum(),
+                    sources: Some(group.len()),
+                }
+            })
+            .collect()
     }
 
-    fn aggregate_to_ohlcv(data: &[serde_json::Value], resolution: &str) -> Vec<OhlcvPoint> {
-        if data.is_empty() {
+    fn aggregate_to_ohlcv(ticks: &[Tick], resolution: &str) -> Vec<OhlcvPoint> {
+        if ticks.is_empty() {
             return vec![];
         }
 
@@ -538,46 +1373,34 @@ impl TickerService {
             _ => 3600,
         };
 
-        // Group data points by time bucket
-        let mut buckets: std::collections::BTreeMap<i64, Vec<&serde_json::Value>> =
+        // Group data points by time bucket, alongside the volume traded since the previous tick
+        let volumes = interval_volumes(ticks);
+        let mut buckets: std::collections::BTreeMap<i64, Vec<(&Tick, Option<f64>)>> =
             std::collections::BTreeMap::new();
 
-        for point in data {
-            if let Some(ts) = point.get("timestamp").and_then(|v| v.as_i64()) {
-                // Convert milliseconds to seconds and bucket
-                let ts_secs = ts / 1000;
-                let bucket = (ts_secs / interval_secs) * interval_secs;
-                buckets.entry(bucket).or_default().push(point);
-            }
+        for (tick, traded) in ticks.iter().zip(volumes) {
+            let bucket = (tick.timestamp_secs() / interval_secs) * interval_secs;
+            buckets.entry(bucket).or_default().push((tick, traded));
         }
 
         // Convert buckets to OHLCV
         buckets
             .into_iter()
             .map(|(timestamp, points)| {
-                let mut open = 0.0;
+                let open = points.first().and_then(|(t, _)| t.price()).unwrap_or(0.0);
+                let close = points.last().and_then(|(t, _)| t.price()).unwrap_or(0.0);
                 let mut high = f64::MIN;
                 let mut low = f64::MAX;
-                let mut close = 0.0;
                 let mut volume = 0.0;
 
-                if let Some(first) = points.first() {
-                    open = first.get("last").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                }
-                if let Some(last) = points.last() {
-                    close = last.get("last").and_then(|v| v.as_f64()).unwrap_or(0.0);
-                }
-
-                for p in &points {
-                    if let Some(h) = p.get("high").and_then(|v| v.as_f64()) {
+                for (p, traded) in &points {
+                    if let Some(h) = p.high {
                         high = high.max(h);
                     }
-                    if let Some(l) = p.get("low").and_then(|v| v.as_f64()) {
+                    if let Some(l) = p.low {
                         low = low.min(l);
                     }
-                    if let Some(v) = p.get("quoteVolume").and_then(|v| v.as_f64()) {
-                        volume = v; // Use latest as it's cumulative
-                    }
+                    volume += traded.unwrap_or(0.0);
                 }
 
                 // Fix edge cases
@@ -594,9 +1417,185 @@ impl TickerService {
                     high,
                     low,
                     close,
-                    volume,
+                    // Per-tick estimates are noisy; only the bucket total is clamped
+                    volume: volume.max(0.0),
+                    sources: None,
                 }
             })
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::catalog_index::CatalogConfig;
+    use crate::infrastructure::{FilesystemRepository, MemoryCacheRepository};
+
+    fn window(from: &str, to: &str) -> DateWindow {
+        DateWindow::resolve(None, Some(from), Some(to), "today").unwrap()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn resolve_named_ranges() {
+        let today = Utc::now().date_naive();
+        let week = DateWindow::resolve(Some("7d"), None, None, "today").unwrap();
+        assert_eq!(week.start, today - Duration::days(7));
+        assert_eq!(week.end, today);
+        assert_eq!(week.label, "7d");
+
+        let default = DateWindow::resolve(None, None, None, "30d").unwrap();
+        assert_eq!(default.label, "30d");
+        assert_eq!(default.span_days(), 31);
+
+        assert!(DateWindow::resolve(Some("1y"), None, None, "today").is_err());
+    }
+
+    #[test]
+    fn resolve_explicit_bounds() {
+        let w = window("2025-12-01", "2025-12-31");
+        assert_eq!(w.start, date("2025-12-01"));
+        assert_eq!(w.end, date("2025-12-31"));
+        assert_eq!(w.label, "2025-12-01..2025-12-31");
+
+        // RFC 3339 and unix seconds or milliseconds reduce to the UTC date
+        let w = window("2025-12-01T23:30:00-02:00", "1766966400000");
+        assert_eq!(w.start, date("2025-12-02"));
+        assert_eq!(w.end, date("2025-12-29"));
+        assert_eq!(window("1766966400", "1766966400").start, date("2025-12-29"));
+
+        let today = Utc::now().date_naive();
+        let open_ended = DateWindow::resolve(None, Some("2025-12-01"), None, "today").unwrap();
+        assert_eq!(open_ended.end, today);
+        let future_end = window("2025-12-01", &(today + Duration::days(10)).to_string());
+        assert_eq!(future_end.end, today);
+    }
+
+    #[test]
+    fn resolve_rejects_invalid_windows() {
+        let today = Utc::now().date_naive();
+        let tomorrow = (today + Duration::days(1)).to_string();
+        let cases = [
+            (Some("7d"), Some("2025-12-01"), None),
+            (None, None, Some("2025-12-01")),
+            (None, Some("2025-12-31"), Some("2025-12-01")),
+            (None, Some(tomorrow.as_str()), None),
+            (None, Some("12/01/2025"), None),
+        ];
+        for (range, from, to) in cases {
+            assert!(
+                DateWindow::resolve(range, from, to, "today").is_err(),
+                "accepted range={:?} from={:?} to={:?}",
+                range,
+                from,
+                to
+            );
+        }
+    }
+
+    #[test]
+    fn span_days_is_inclusive() {
+        assert_eq!(window("2025-12-29", "2025-12-29").span_days(), 1);
+        // 31 + 28 + 31 + 2 days: exactly the stats limit
+        assert_eq!(
+            window("2025-01-01", "2025-04-02").span_days(),
+            MAX_STATS_SPAN_DAYS
+        );
+        assert!(window("2025-01-01", "2025-04-03").span_days() > MAX_STATS_SPAN_DAYS);
+        assert_eq!(
+            window("2025-12-27", "2025-12-29").span_days(),
+            max_history_span_days("1m")
+        );
+    }
+
+    #[test]
+    fn recent_days_stay_within_window() {
+        assert_eq!(
+            TickerService::recent_days(date("2025-12-01"), date("2025-12-29")),
+            vec![date("2025-12-29"), date("2025-12-28"), date("2025-12-27")]
+        );
+        assert_eq!(
+            TickerService::recent_days(date("2025-12-29"), date("2025-12-29")),
+            vec![date("2025-12-29")]
+        );
+    }
+
+    #[tokio::test]
+    async fn single_day_stats_fall_back_to_summary() {
+        let root = std::env::temp_dir().join(format!("ticker-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("data/kaspa/ascendex/2025/12");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("2025-12-01.json"),
+            r#"{"open": 0.04, "high": 0.05, "low": 0.03, "close": 0.045, "quoteVolume": 3000, "trades": 3}"#,
+        )
+        .unwrap();
+        let config = RepoConfig {
+            source: "local".to_string(),
+            owner: "KaspaDev".to_string(),
+            repo: "Kaspa-Exchange-Data".to_string(),
+            root: Some(root.to_string_lossy().into_owned()),
+        };
+        let repo: Arc<dyn ContentRepository> = Arc::new(FilesystemRepository::new(&root));
+        let catalog = Arc::new(CatalogIndex::new(
+            repo.clone(),
+            Arc::new(MemoryCacheRepository::new(1 << 20)),
+            config.clone(),
+            CatalogConfig::default(),
+        ));
+
+        let day = date("2025-12-01");
+        let stats = TickerService::fetch_exchange_stats(
+            repo,
+            config,
+            catalog,
+            "kaspa".to_string(),
+            "ascendex".to_string(),
+            day,
+            day,
+        )
+        .await
+        .unwrap();
+        assert_eq!(stats.last, Some(0.045));
+        assert_eq!(stats.high, Some(0.05));
+        assert_eq!(stats.volume_24h, Some(1000.0));
+        assert_eq!(stats.data_points, 3);
+        // No raw file to read the market's quote from
+        assert_eq!(stats.quote, None);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn ohlcv_bucket_volume_skips_rolling_reset() {
+        // Hourly buckets from 2025-12-29T00:00:00Z; the exchange resets its
+        // rolling 24h figure two minutes into the first hour
+        let file: RawTickFile = serde_json::from_value(serde_json::json!({
+            "data": [
+                { "timestamp": 1_766_966_400_000i64, "last": 1.0, "quoteVolume": 86_400.0 },
+                { "timestamp": 1_766_966_460_000i64, "last": 1.2, "quoteVolume": 86_460.0 },
+                { "timestamp": 1_766_966_520_000i64, "last": 0.9, "quoteVolume": 100.0 },
+                { "timestamp": 1_766_966_580_000i64, "last": 1.1, "quoteVolume": 160.0 },
+                { "timestamp": 1_766_970_000_000i64, "last": 1.3, "quoteVolume": 170.0 },
+            ],
+        }))
+        .unwrap();
+
+        let points = TickerService::aggregate_to_ohlcv(&file.ticks, "1h");
+        assert_eq!(points.len(), 2);
+        let first = &points[0];
+        assert_eq!(first.timestamp, 1_766_966_400);
+        assert_eq!((first.open, first.close), (1.0, 1.1));
+        // 120 before the reset, nothing for the reset tick, 60 + 100/1440 after it
+        let expected = 120.0 + 60.0 + 100.0 * 60.0 / 86_400.0;
+        assert!((first.volume - expected).abs() < 1e-9, "{}", first.volume);
+        // The next hour continues from the post-reset figure
+        assert_eq!(points[1].timestamp, 1_766_970_000);
+        let expected = 10.0 + 160.0 * 3420.0 / 86_400.0;
+        assert!((points[1].volume - expected).abs() < 1e-9, "{}", points[1].volume);
+    }
+}
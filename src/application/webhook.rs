@@ -0,0 +1,155 @@
+//! GitHub push webhook handling.
+//!
+//! Collectors update the data repository by pushing commits. A push to the
+//! default branch of a whitelisted `github` repository invalidates the cache
+//! entries derived from the files it touched (see
+//! [`CacheAdminService::invalidate_changes`]) and refreshes the catalog index
+//! for them (see [`CatalogIndex::refresh_changes`]), so new data is served
+//! without waiting for TTLs or the next catalog walk.
+
+use crate::application::{CacheAdminService, CatalogIndex};
+use crate::domain::RepoConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// Source of the repositories a GitHub webhook can refer to.
+const GITHUB_SOURCE: &str = "github";
+
+/// Most commits GitHub includes in a push payload; larger pushes are truncated.
+const MAX_PUSH_COMMITS: usize = 20;
+
+/// The parts of a GitHub `push` event payload used for invalidation.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushEvent {
+    /// Pushed ref, e.g. `refs/heads/main`
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    /// Commit SHA after the push
+    #[serde(default)]
+    pub after: String,
+    pub repository: PushRepository,
+    /// Pushed commits (at most `MAX_PUSH_COMMITS`)
+    #[serde(default)]
+    pub commits: Vec<PushCommit>,
+}
+
+/// Repository a push event belongs to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushRepository {
+    /// Repository in `owner/repo` form
+    pub full_name: String,
+    /// Branch the data is served from
+    #[serde(default)]
+    pub default_branch: Option<String>,
+}
+
+/// Files touched by one pushed commit.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushCommit {
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub modified: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+impl PushEvent {
+    /// Whether the push updated the repository's default branch.
+    fn is_default_branch(&self) -> bool {
+        let branch = self.repository.default_branch.as_deref().unwrap_or("main");
+        self.git_ref == format!("refs/heads/{}", branch)
+    }
+
+    /// Paths touched by the push, or `None` when the payload may be incomplete.
+    fn changed_paths(&self) -> Option<Vec<String>> {
+        if self.commits.is_empty() || self.commits.len() >= MAX_PUSH_COMMITS {
+            return None;
+        }
+        let paths: BTreeSet<&String> = self
+            .commits
+            .iter()
+            .flat_map(|c| c.added.iter().chain(&c.modified).chain(&c.removed))
+            .collect();
+        Some(paths.into_iter().cloned().collect())
+    }
+}
+
+/// Response structure for the GitHub webhook endpoint.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WebhookResponse {
+    /// GitHub event name (`X-GitHub-Event`)
+    pub event: String,
+    /// `invalidated`, `sync_requested` or `ignored`
+    pub status: String,
+    /// Number of cache keys removed
+    pub deleted: usize,
+}
+
+/// Service applying GitHub push events to the cache.
+#[derive(Clone)]
+pub struct WebhookService {
+    cache_admin: Arc<CacheAdminService>,
+    catalog: Arc<CatalogIndex>,
+    allowed_repos: Vec<RepoConfig>,
+}
+
+impl WebhookService {
+    pub fn new(
+        cache_admin: Arc<CacheAdminService>,
+        catalog: Arc<CatalogIndex>,
+        allowed_repos: Vec<RepoConfig>,
+    ) -> Self {
+        Self {
+            cache_admin,
+            catalog,
+            allowed_repos,
+        }
+    }
+
+    /// Whitelisted `github` repositories whose served data changed with `event`.
+    pub fn affected_repos(&self, event: &PushEvent) -> Vec<RepoConfig> {
+        if !event.is_default_branch() {
+            return Vec::new();
+        }
+        self.allowed_repos
+            .iter()
+            .filter(|r| {
+                r.source == GITHUB_SOURCE
+                    && format!("{}/{}", r.owner, r.repo)
+                        .eq_ignore_ascii_case(&event.repository.full_name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Invalidate the entries of `repos` derived from the files `event` touched
+    /// and refresh the catalog index for them.
+    ///
+    /// Returns the number of keys removed. A failed catalog refresh is logged
+    /// and left to the next periodic walk.
+    pub async fn invalidate(
+        &self,
+        event: &PushEvent,
+        repos: &[RepoConfig],
+    ) -> anyhow::Result<usize> {
+        let changed_paths = event.changed_paths();
+        let mut deleted = 0;
+        for repo in repos {
+            deleted += self
+                .cache_admin
+                .invalidate_changes(repo, changed_paths.as_deref())
+                .await?;
+            if let Err(e) = self
+                .catalog
+                .refresh_changes(repo, changed_paths.as_deref())
+                .await
+            {
+                tracing::warn!("Catalog refresh after push failed: {}", e);
+            }
+        }
+        Ok(deleted)
+    }
+}
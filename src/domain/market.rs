@@ -0,0 +1,781 @@
+//! Market data entities stored in the exchange data repository.
+//!
+//! Every `data/{token}/{exchange}/{year}/{month}/` directory holds two kinds of files:
+//! - `YYYY-MM-DD-raw.json`: a `RawTickFile` with one `Tick` per collector run (~1/minute)
+//! - `YYYY-MM-DD.json`: a `DailySummary` with precomputed daily OHLCV figures
+//!
+//! The collectors have changed over time, so deserialization is deliberately tolerant:
+//! - numeric fields may be `null`, numbers, or numeric strings
+//! - timestamps may be in seconds or milliseconds (normalized to milliseconds)
+//! - volumes may appear as both `quoteVolume` and `quote_volume` (camelCase wins)
+//! - unknown fields are ignored
+//!
+//! Records that cannot be used (no timestamp, no price) are dropped while parsing a
+//! `RawTickFile` and counted in its `ValidationReport`.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+/// Ticks further apart than this are a collection gap; no volume is attributed across it.
+const MAX_VOLUME_GAP_SECS: f64 = 3600.0;
+
+/// A rolling volume dropping below this fraction of the previous value is a counter reset.
+const VOLUME_RESET_RATIO: f64 = 0.5;
+
+/// Length of the exchanges' rolling volume window.
+const ROLLING_WINDOW_SECS: f64 = 86_400.0;
+
+/// Timestamps below this value are taken to be in seconds rather than milliseconds.
+///
+/// `1e11` milliseconds is March 1973, while `1e11` seconds is the year 5138.
+pub(crate) const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// A single ticker snapshot from one exchange.
+///
+/// # Fields
+///
+/// - `timestamp`: Snapshot time in Unix milliseconds (normalized from seconds if needed)
+/// - `last`/`close`: Last traded price (`price()` prefers `last`)
+/// - `high`/`low`/`open`: Exchange-reported rolling 24h figures
+/// - `base_volume`/`quote_volume`: Exchange-reported rolling 24h volumes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tick {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    /// Last traded price
+    pub last: Option<f64>,
+    /// Best bid
+    pub bid: Option<f64>,
+    /// Best ask
+    pub ask: Option<f64>,
+    /// Rolling 24h open
+    pub open: Option<f64>,
+    /// Rolling 24h high
+    pub high: Option<f64>,
+    /// Rolling 24h low
+    pub low: Option<f64>,
+    /// Close price (mirrors `last` on most exchanges)
+    pub close: Option<f64>,
+    /// Rolling 24h volume in the base asset
+    pub base_volume: Option<f64>,
+    /// Rolling 24h volume in the quote asset
+    pub quote_volume: Option<f64>,
+    /// Absolute 24h price change
+    pub change: Option<f64>,
+    /// 24h price change percentage
+    pub percentage: Option<f64>,
+    /// Exchange-reported VWAP
+    pub vwap: Option<f64>,
+    /// Market symbol as reported by the exchange (e.g. `TBDAI/USDT`, `BTC_USDT`)
+    pub symbol: Option<String>,
+    /// Quote asset before any collector-side conversion (e.g. `MUSDT`)
+    pub original_quote: Option<String>,
+    /// Quote asset the collector converted prices into, if it did
+    pub converted_quote: Option<String>,
+}
+
+impl Tick {
+    /// Best available trade price: `last`, falling back to `close`.
+    pub fn price(&self) -> Option<f64> {
+        self.last.or(self.close)
+    }
+
+    /// Timestamp in Unix seconds.
+    pub fn timestamp_secs(&self) -> i64 {
+        self.timestamp / 1000
+    }
+
+    /// Quote asset the prices are expressed in, upper-cased.
+    ///
+    /// A collector-side conversion target wins, then the quote parsed from
+    /// `symbol`, then `original_quote`. `None` when nothing identifies it.
+    pub fn quote_asset(&self) -> Option<String> {
+        self.converted_quote
+            .clone()
+            .or_else(|| self.symbol.as_deref().and_then(symbol_quote))
+            .or_else(|| self.original_quote.clone())
+            .map(|q| q.trim().to_uppercase())
+            .filter(|q| !q.is_empty())
+    }
+}
+
+/// Quote assets recognized at the end of separator-less symbols (`SLOWUSDT`),
+/// longest first so `USDT` is not mistaken for `USD`.
+const KNOWN_QUOTES: &[&str] = &[
+    "FDUSD", "USDT", "USDC", "BUSD", "TUSD", "USD", "DAI", "EUR", "TRY", "BTC", "ETH", "KAS",
+];
+
+/// Extract the quote asset from a market symbol.
+///
+/// Handles `BASE/QUOTE`, `BASE_QUOTE`, `BASE-QUOTE`, derivative suffixes
+/// (`BASE/QUOTE:SETTLE`) and concatenated symbols ending in a known quote.
+fn symbol_quote(symbol: &str) -> Option<String> {
+    let symbol = symbol.split(':').next().unwrap_or_default().trim();
+    if let Some((_, quote)) = symbol.rsplit_once(['/', '_', '-']) {
+        return Some(quote.to_string()).filter(|q| !q.is_empty());
+    }
+    let upper = symbol.to_uppercase();
+    KNOWN_QUOTES
+        .iter()
+        .find(|q| upper.len() > q.len() && upper.ends_with(*q))
+        .map(|q| q.to_string())
+}
+
+/// Raw wire format of a tick, before normalization.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TickDto {
+    #[serde(deserialize_with = "lenient_i64")]
+    timestamp: Option<i64>,
+    datetime: Option<String>,
+    #[serde(deserialize_with = "lenient_f64")]
+    last: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    bid: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    ask: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    open: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    high: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    low: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    close: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    volume: Option<f64>,
+    #[serde(rename = "baseVolume", deserialize_with = "lenient_f64")]
+    base_volume_camel: Option<f64>,
+    #[serde(rename = "base_volume", deserialize_with = "lenient_f64")]
+    base_volume_snake: Option<f64>,
+    #[serde(rename = "quoteVolume", deserialize_with = "lenient_f64")]
+    quote_volume_camel: Option<f64>,
+    #[serde(rename = "quote_volume", deserialize_with = "lenient_f64")]
+    quote_volume_snake: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    change: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    percentage: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    vwap: Option<f64>,
+    symbol: Option<String>,
+    original_quote: Option<String>,
+    quote_conversion: Option<QuoteConversionDto>,
+}
+
+/// Collector-side quote conversion note (e.g. `MUSDT` prices scaled into `USDT`).
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct QuoteConversionDto {
+    to: Option<String>,
+}
+
+/// Why a tick record was rejected while parsing.
+enum TickRejection {
+    Malformed,
+    MissingTimestamp,
+    MissingPrice,
+}
+
+impl TickDto {
+    fn into_tick(self) -> Result<(Tick, bool), TickRejection> {
+        let raw_timestamp = self
+            .timestamp
+            .or_else(|| self.datetime.as_deref().and_then(parse_datetime_millis));
+        let Some(raw_timestamp) = raw_timestamp else {
+            return Err(TickRejection::MissingTimestamp);
+        };
+        let (timestamp, normalized) = normalize_timestamp(raw_timestamp);
+
+        let tick = Tick {
+            timestamp,
+            last: self.last,
+            bid: self.bid,
+            ask: self.ask,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            base_volume: self
+                .base_volume_camel
+                .or(self.base_volume_snake)
+                .or(self.volume),
+            quote_volume: self.quote_volume_camel.or(self.quote_volume_snake),
+            change: self.change,
+            percentage: self.percentage,
+            vwap: self.vwap,
+            symbol: self.symbol,
+            original_quote: self.original_quote,
+            converted_quote: self.quote_conversion.and_then(|c| c.to),
+        };
+
+        match tick.price() {
+            Some(p) if p > 0.0 => Ok((tick, normalized)),
+            _ => Err(TickRejection::MissingPrice),
+        }
+    }
+}
+
+/// Summary of what happened while parsing a raw tick file.
+///
+/// Rejected and repaired records are counted rather than failing the whole file,
+/// so one bad collector run does not hide a day of data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Records present in the `data` array
+    pub total: usize,
+    /// Records kept after validation
+    pub accepted: usize,
+    /// Records that were not JSON objects
+    pub malformed: usize,
+    /// Records without a usable timestamp
+    pub missing_timestamp: usize,
+    /// Records without a positive `last`/`close` price
+    pub missing_price: usize,
+    /// Records dropped because another record had the same timestamp
+    pub duplicates: usize,
+    /// Records whose timestamp was converted from seconds to milliseconds
+    pub normalized_timestamps: usize,
+    /// Whether records had to be re-sorted by timestamp
+    pub reordered: bool,
+    /// `count` declared in the file header, if present
+    pub declared_count: Option<usize>,
+}
+
+impl ValidationReport {
+    /// Number of records dropped for any reason.
+    pub fn rejected(&self) -> usize {
+        self.total - self.accepted
+    }
+
+    /// Whether the file parsed without dropping or repairing anything.
+    pub fn is_clean(&self) -> bool {
+        self.rejected() == 0
+            && !self.reordered
+            && self.declared_count.is_none_or(|c| c == self.total)
+    }
+}
+
+/// Contents of a `YYYY-MM-DD-raw.json` file.
+///
+/// Ticks are sorted by timestamp with duplicates removed.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "RawTickFileDto")]
+pub struct RawTickFile {
+    /// Validated ticks in ascending timestamp order
+    pub ticks: Vec<Tick>,
+    /// What was dropped or repaired while parsing
+    pub report: ValidationReport,
+}
+
+#[derive(Deserialize)]
+struct RawTickFileDto {
+    #[serde(default, deserialize_with = "lenient_i64")]
+    count: Option<i64>,
+    #[serde(default)]
+    data: Vec<Value>,
+}
+
+impl From<RawTickFileDto> for RawTickFile {
+    fn from(dto: RawTickFileDto) -> Self {
+        let mut report = ValidationReport {
+            total: dto.data.len(),
+            declared_count: dto.count.and_then(|c| usize::try_from(c).ok()),
+            ..Default::default()
+        };
+
+        let mut ticks = Vec::with_capacity(dto.data.len());
+        for value in dto.data {
+            let parsed = serde_json::from_value::<TickDto>(value)
+                .map_err(|_| TickRejection::Malformed)
+                .and_then(TickDto::into_tick);
+            match parsed {
+                Ok((tick, normalized)) => {
+                    if normalized {
+                        report.normalized_timestamps += 1;
+                    }
+                    ticks.push(tick);
+                }
+                Err(TickRejection::Malformed) => report.malformed += 1,
+                Err(TickRejection::MissingTimestamp) => report.missing_timestamp += 1,
+                Err(TickRejection::MissingPrice) => report.missing_price += 1,
+            }
+        }
+
+        if !ticks.windows(2).all(|w| w[0].timestamp <= w[1].timestamp) {
+            report.reordered = true;
+            ticks.sort_by_key(|t| t.timestamp);
+        }
+
+        // Keep the last record seen for each timestamp
+        let before_dedup = ticks.len();
+        ticks.reverse();
+        ticks.dedup_by_key(|t| t.timestamp);
+        ticks.reverse();
+        report.duplicates = before_dedup - ticks.len();
+        report.accepted = ticks.len();
+
+        RawTickFile { ticks, report }
+    }
+}
+
+/// Contents of a `YYYY-MM-DD.json` daily summary file.
+///
+/// Days on which the exchange reported nothing are stored with `null` prices;
+/// use `has_prices()` before relying on the OHLC fields.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "DailySummaryDto")]
+pub struct DailySummary {
+    /// Trading day covered by the summary
+    pub date: Option<NaiveDate>,
+    /// Token identifier
+    pub token: Option<String>,
+    /// Exchange identifier
+    pub exchange: Option<String>,
+    /// First price of the day
+    pub open: Option<f64>,
+    /// Highest price of the day
+    pub high: Option<f64>,
+    /// Lowest price of the day
+    pub low: Option<f64>,
+    /// Last price of the day
+    pub close: Option<f64>,
+    /// Sum of the day's rolling 24h base volume snapshots (as written by the collector)
+    pub volume: Option<f64>,
+    /// Sum of the day's rolling 24h quote volume snapshots (as written by the collector);
+    /// use `estimated_volume()` for the day's traded volume
+    pub quote_volume: Option<f64>,
+    /// Absolute price change over the day
+    pub change: Option<f64>,
+    /// Percentage price change over the day
+    pub percentage: Option<f64>,
+    /// Number of tick snapshots the summary was built from
+    pub trades: Option<u64>,
+    /// Mean of the tick prices
+    pub avg_price: Option<f64>,
+    /// Volume-weighted average price
+    pub vwap: Option<f64>,
+    /// First tick time in Unix milliseconds
+    pub first_update: Option<i64>,
+    /// Last tick time in Unix milliseconds
+    pub last_update: Option<i64>,
+}
+
+impl DailySummary {
+    /// Whether the summary carries a usable close price.
+    pub fn has_prices(&self) -> bool {
+        matches!(self.close, Some(c) if c > 0.0)
+    }
+
+    /// Estimated quote volume traded during the day.
+    ///
+    /// The collector sums every rolling 24h snapshot into `quote_volume`, so the
+    /// mean snapshot (`quote_volume / trades`) approximates one day of trading.
+    pub fn estimated_volume(&self) -> Option<f64> {
+        match (self.quote_volume, self.trades) {
+            (Some(total), Some(n)) if n > 0 => Some(total / n as f64),
+            _ => None,
+        }
+    }
+
+    /// Rebuild a summary for `date` from that day's raw ticks.
+    ///
+    /// High and low come from traded prices rather than the ticks' rolling 24h
+    /// figures, so they stay within the day. Volumes follow the collector's
+    /// convention of summing the rolling 24h snapshots (see `estimated_volume()`).
+    /// Returns `None` when no tick has a price.
+    pub fn from_ticks(date: NaiveDate, ticks: &[Tick]) -> Option<Self> {
+        let priced: Vec<(&Tick, f64)> = ticks
+            .iter()
+            .filter_map(|t| t.price().map(|p| (t, p)))
+            .collect();
+        let (first, open) = *priced.first()?;
+        let (last, close) = *priced.last()?;
+
+        let high = priced.iter().map(|(_, p)| *p).fold(f64::MIN, f64::max);
+        let low = priced.iter().map(|(_, p)| *p).fold(f64::MAX, f64::min);
+        let avg_price = priced.iter().map(|(_, p)| *p).sum::<f64>() / priced.len() as f64;
+        let change = close - open;
+
+        Some(DailySummary {
+            date: Some(date),
+            token: None,
+            exchange: None,
+            open: Some(open),
+            high: Some(high),
+            low: Some(low),
+            close: Some(close),
+            volume: snapshot_total(ticks.iter().map(|t| t.base_volume), priced.len()),
+            quote_volume: snapshot_total(ticks.iter().map(|t| t.quote_volume), priced.len()),
+            change: Some(change),
+            percentage: (open > 0.0).then(|| change / open * 100.0),
+            trades: Some(priced.len() as u64),
+            avg_price: Some(avg_price),
+            vwap: None,
+            first_update: Some(first.timestamp),
+            last_update: Some(last.timestamp),
+        })
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DailySummaryDto {
+    date: Option<String>,
+    token: Option<String>,
+    exchange: Option<String>,
+    #[serde(deserialize_with = "lenient_f64")]
+    open: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    high: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    low: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    close: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    volume: Option<f64>,
+    #[serde(rename = "quoteVolume", deserialize_with = "lenient_f64")]
+    quote_volume_camel: Option<f64>,
+    #[serde(rename = "quote_volume", deserialize_with = "lenient_f64")]
+    quote_volume_snake: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    change: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    percentage: Option<f64>,
+    #[serde(deserialize_with = "lenient_i64")]
+    trades: Option<i64>,
+    #[serde(rename = "avgPrice", deserialize_with = "lenient_f64")]
+    avg_price: Option<f64>,
+    #[serde(deserialize_with = "lenient_f64")]
+    vwap: Option<f64>,
+    #[serde(deserialize_with = "lenient_i64")]
+    timestamp: Option<i64>,
+    #[serde(rename = "lastUpdate", deserialize_with = "lenient_i64")]
+    last_update: Option<i64>,
+}
+
+impl From<DailySummaryDto> for DailySummary {
+    fn from(dto: DailySummaryDto) -> Self {
+        DailySummary {
+            date: dto
+                .date
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            token: dto.token,
+            exchange: dto.exchange,
+            open: dto.open,
+            high: dto.high,
+            low: dto.low,
+            close: dto.close,
+            volume: dto.volume,
+            quote_volume: dto.quote_volume_camel.or(dto.quote_volume_snake),
+            change: dto.change,
+            percentage: dto.percentage,
+            trades: dto.trades.and_then(|t| u64::try_from(t).ok()),
+            avg_price: dto.avg_price,
+            vwap: dto.vwap,
+            first_update: dto.timestamp.map(|t| normalize_timestamp(t).0),
+            last_update: dto.last_update.map(|t| normalize_timestamp(t).0),
+        }
+    }
+}
+
+/// Scale the mean of the reported snapshots to `count` snapshots.
+///
+/// Keeps `total / count` equal to the mean even when some ticks omit the field.
+fn snapshot_total(values: impl Iterator<Item = Option<f64>>, count: usize) -> Option<f64> {
+    let reported: Vec<f64> = values.flatten().collect();
+    if reported.is_empty() {
+        return None;
+    }
+    let mean = reported.iter().sum::<f64>() / reported.len() as f64;
+    Some(mean * count as f64)
+}
+
+/// Estimate the quote volume traded between each tick and the one before it.
+///
+/// Exchanges report `quote_volume` over a rolling 24h window, so the raw value
+/// of one tick says nothing about the minute it covers. Between consecutive
+/// ticks `a` and `b`, `dt` seconds apart, the window gains what traded in
+/// `(a, b]` and loses what traded in the first `dt` seconds of `a`'s window.
+/// Assuming that oldest slice traded at the window's average rate:
+///
+/// ```text
+/// traded(a, b] ≈ V(b) - V(a) + V(a) * dt / 86400
+/// ```
+///
+/// Individual estimates can be negative because exchanges' rolling figures are
+/// noisy; summed over a bucket the deltas telescope, so callers should clamp the
+/// bucket total rather than each entry (clamping per tick inflates volume).
+///
+/// No volume is attributed (the entry is `None`) for the first tick, for ticks
+/// missing `quote_volume`, across gaps longer than an hour, or when the rolling
+/// value drops below half of the previous one (an exchange-side reset).
+///
+/// `ticks` must belong to a single market and be sorted by timestamp.
+pub fn interval_volumes(ticks: &[Tick]) -> Vec<Option<f64>> {
+    let mut volumes = Vec::with_capacity(ticks.len());
+    let mut previous: Option<&Tick> = None;
+
+    for tick in ticks {
+        let estimate = previous.and_then(|prev| {
+            let (v_prev, v_cur) = (prev.quote_volume?, tick.quote_volume?);
+            let dt = (tick.timestamp - prev.timestamp) as f64 / 1000.0;
+            if dt <= 0.0 || dt > MAX_VOLUME_GAP_SECS || v_cur < v_prev * VOLUME_RESET_RATIO {
+                return None;
+            }
+            Some(v_cur - v_prev + v_prev * dt / ROLLING_WINDOW_SECS)
+        });
+        volumes.push(estimate);
+        previous = Some(tick);
+    }
+
+    volumes
+}
+
+/// Convert a seconds-or-milliseconds timestamp to milliseconds.
+///
+/// Returns the normalized value and whether a conversion was applied.
+fn normalize_timestamp(ts: i64) -> (i64, bool) {
+    if ts.abs() < MILLIS_THRESHOLD {
+        (ts * 1000, true)
+    } else {
+        (ts, false)
+    }
+}
+
+/// Parse collector `datetime` strings (RFC 3339 or naive ISO 8601, assumed UTC).
+fn parse_datetime_millis(s: &str) -> Option<i64> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp_millis());
+    }
+    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc().timestamp_millis())
+}
+
+/// Interpret a JSON value as a finite float, accepting numeric strings.
+fn value_to_f64(value: &Value) -> Option<f64> {
+    let parsed = match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    parsed.filter(|v| v.is_finite())
+}
+
+fn lenient_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(value_to_f64(&value))
+}
+
+fn lenient_i64<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(match &value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        _ => value_to_f64(&value).map(|f| f as i64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse_tick(value: Value) -> Tick {
+        let dto: TickDto = serde_json::from_value(value).unwrap();
+        match dto.into_tick() {
+            Ok((tick, _)) => tick,
+            Err(_) => panic!("tick rejected"),
+        }
+    }
+
+    /// Tick `secs` seconds after 2025-12-29T00:00:00Z.
+    fn volume_tick(secs: i64, quote_volume: f64) -> Tick {
+        let timestamp = (1_766_966_400 + secs) * 1000;
+        parse_tick(json!({ "timestamp": timestamp, "last": 1.0, "quoteVolume": quote_volume }))
+    }
+
+    #[test]
+    fn tick_accepts_numeric_strings_and_nulls() {
+        let tick = parse_tick(json!({
+            "timestamp": "1766966400000",
+            "last": "0.0421",
+            "bid": null,
+            "ask": "not a number",
+            "high": 0.05,
+            "unknown": { "ignored": true },
+        }));
+        assert_eq!(tick.timestamp, 1_766_966_400_000);
+        assert_eq!(tick.last, Some(0.0421));
+        assert_eq!(tick.bid, None);
+        assert_eq!(tick.ask, None);
+        assert_eq!(tick.high, Some(0.05));
+    }
+
+    #[test]
+    fn tick_normalizes_seconds_and_datetime() {
+        let dto: TickDto =
+            serde_json::from_value(json!({ "timestamp": 1_766_966_400, "close": 2.0 })).unwrap();
+        let Ok((tick, normalized)) = dto.into_tick() else {
+            panic!("tick rejected");
+        };
+        assert!(normalized);
+        assert_eq!(tick.timestamp, 1_766_966_400_000);
+        assert_eq!(tick.price(), Some(2.0));
+
+        let tick = parse_tick(json!({ "datetime": "2025-12-29T00:00:00.500Z", "last": 1.0 }));
+        assert_eq!(tick.timestamp, 1_766_966_400_500);
+        let tick = parse_tick(json!({ "datetime": "2025-12-29T00:00:00", "last": 1.0 }));
+        assert_eq!(tick.timestamp, 1_766_966_400_000);
+    }
+
+    #[test]
+    fn tick_prefers_camel_case_volumes() {
+        let tick = parse_tick(json!({
+            "timestamp": 1_766_966_400_000i64,
+            "last": 1.0,
+            "quoteVolume": "10",
+            "quote_volume": 20,
+            "baseVolume": null,
+            "base_volume": 30,
+            "volume": 40,
+        }));
+        assert_eq!(tick.quote_volume, Some(10.0));
+        assert_eq!(tick.base_volume, Some(30.0));
+
+        let tick = parse_tick(json!({
+            "timestamp": 1_766_966_400_000i64,
+            "last": 1.0,
+            "quote_volume": 20,
+            "volume": 40,
+        }));
+        assert_eq!(tick.quote_volume, Some(20.0));
+        assert_eq!(tick.base_volume, Some(40.0));
+    }
+
+    #[test]
+    fn tick_quote_asset_sources() {
+        let tick = parse_tick(json!({
+            "timestamp": 1_766_966_400_000i64,
+            "last": 1.0,
+            "symbol": "TBDAI/MUSDT",
+            "original_quote": "MUSDT",
+            "quote_conversion": { "to": "usdt" },
+        }));
+        assert_eq!(tick.quote_asset().as_deref(), Some("USDT"));
+
+        assert_eq!(symbol_quote("BTC_USDT").as_deref(), Some("USDT"));
+        assert_eq!(symbol_quote("KAS/USDT:USDT").as_deref(), Some("USDT"));
+        assert_eq!(symbol_quote("SLOWUSDT").as_deref(), Some("USDT"));
+        assert_eq!(symbol_quote("KASBTC").as_deref(), Some("BTC"));
+        assert_eq!(symbol_quote("USDT"), None);
+    }
+
+    #[test]
+    fn raw_file_sorts_dedups_and_reports() {
+        let file: RawTickFile = serde_json::from_value(json!({
+            "count": 7,
+            "data": [
+                { "timestamp": 3_000_000_000_000i64, "last": 3.0 },
+                { "timestamp": 1_000_000_000_000i64, "last": 1.0 },
+                { "timestamp": 3_000_000_000_000i64, "last": 4.0 },
+                { "timestamp": 2_000_000_000, "last": 2.0 },
+                { "last": 5.0 },
+                { "timestamp": 4_000_000_000_000i64, "last": 0 },
+                "not an object",
+            ],
+        }))
+        .unwrap();
+
+        let prices: Vec<_> = file.ticks.iter().map(|t| (t.timestamp, t.last)).collect();
+        assert_eq!(
+            prices,
+            vec![
+                (1_000_000_000_000, Some(1.0)),
+                (2_000_000_000_000, Some(2.0)),
+                (3_000_000_000_000, Some(4.0)),
+            ]
+        );
+        assert_eq!(
+            file.report,
+            ValidationReport {
+                total: 7,
+                accepted: 3,
+                malformed: 1,
+                missing_timestamp: 1,
+                missing_price: 1,
+                duplicates: 1,
+                normalized_timestamps: 1,
+                reordered: true,
+                declared_count: Some(7),
+            }
+        );
+        assert_eq!(file.report.rejected(), 4);
+        assert!(!file.report.is_clean());
+    }
+
+    #[test]
+    fn raw_file_clean_when_nothing_repaired() {
+        let file: RawTickFile = serde_json::from_value(json!({
+            "count": 2,
+            "data": [
+                { "timestamp": 1_000_000_000_000i64, "last": 1.0 },
+                { "timestamp": 1_000_000_060_000i64, "close": "1.5" },
+            ],
+        }))
+        .unwrap();
+        assert_eq!(file.ticks.len(), 2);
+        assert!(file.report.is_clean());
+
+        let mismatched: RawTickFile = serde_json::from_value(json!({
+            "count": 3,
+            "data": [{ "timestamp": 1_000_000_000_000i64, "last": 1.0 }],
+        }))
+        .unwrap();
+        assert!(!mismatched.report.is_clean());
+    }
+
+    #[test]
+    fn interval_volumes_estimates_rolling_deltas() {
+        let ticks = [volume_tick(0, 86_400.0), volume_tick(60, 86_460.0)];
+        let volumes = interval_volumes(&ticks);
+        assert_eq!(volumes[0], None);
+        // 60 new units, plus the minute that left the window at the average rate
+        assert_eq!(volumes[1], Some(120.0));
+    }
+
+    #[test]
+    fn interval_volumes_skips_resets_and_gaps() {
+        let ticks = [
+            volume_tick(0, 1000.0),
+            volume_tick(60, 499.0),
+            volume_tick(120, 250.0),
+            volume_tick(120 + 3601, 260.0),
+            volume_tick(120 + 3601 + 60, 240.0),
+        ];
+        let volumes = interval_volumes(&ticks);
+        // Below half the previous value: a counter reset
+        assert_eq!(volumes[1], None);
+        // Exactly half is still a normal (negative) delta
+        assert!(volumes[2].is_some_and(|v| v < 0.0));
+        // Over an hour apart
+        assert_eq!(volumes[3], None);
+        assert!(volumes[4].is_some());
+    }
+
+    #[test]
+    fn interval_volumes_needs_quote_volume() {
+        let mut missing = volume_tick(60, 0.0);
+        missing.quote_volume = None;
+        let ticks = [volume_tick(0, 100.0), missing, volume_tick(120, 100.0)];
+        assert_eq!(interval_volumes(&ticks), vec![None, None, None]);
+    }
+}
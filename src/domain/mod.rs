@@ -5,8 +5,17 @@
 //! - Repository traits that define data access interfaces
 //! - Domain entities representing core business concepts
 //! - Value objects and types used throughout the application
+//! - Market data entities for the tick and daily summary files (see [`market`])
+//! - Cross-exchange price composition (see [`pricing`])
+
+pub mod market;
+pub mod pricing;
+
+pub use market::{interval_volumes, DailySummary, RawTickFile, Tick};
 
 use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use ring::rand::{SecureRandom, SystemRandom};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
@@ -24,16 +33,22 @@ use std::fmt::Debug;
 ///     source: "github".to_string(),
 ///     owner: "KaspaDev".to_string(),
 ///     repo: "Kaspa-Exchange-Data".to_string(),
+///     root: None,
 /// };
 /// ```
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 pub struct RepoConfig {
-    /// The source platform (e.g., "github")
+    /// The source platform (e.g., "github", "local")
     pub source: String,
     /// The repository owner or organization name
     pub owner: String,
     /// The repository name
     pub repo: String,
+    /// Directory on disk holding the repository tree (`local` sources only).
+    ///
+    /// Paths such as `data/kaspa/...` are resolved relative to this root.
+    #[serde(default)]
+    pub root: Option<String>,
 }
 
 /// Represents content from a repository (file or directory listing).
@@ -52,6 +67,7 @@ pub struct RepoConfig {
 /// - `html_url`: Browser-viewable URL (optional)
 /// - `download_url`: Direct download URL (optional)
 /// - `url`: API URL for accessing this content
+/// - `size`: File size in bytes, when the backend reports it
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Content {
     /// The name of the file or directory
@@ -74,6 +90,28 @@ pub struct Content {
     pub download_url: Option<String>,
     /// API URL for this content
     pub url: String,
+    /// File size in bytes (files only, when reported by the backend)
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+impl Content {
+    /// Decode the file body into raw bytes.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if the content has no body (e.g. directory entries)
+    /// - Returns error if the encoding is unsupported or the body is malformed
+    pub fn decode(&self) -> anyhow::Result<Vec<u8>> {
+        match (self.content.as_deref(), self.encoding.as_deref()) {
+            (Some(raw), Some("base64")) => {
+                let clean = raw.replace('\n', "");
+                Ok(general_purpose::STANDARD.decode(clean)?)
+            }
+            (Some(_), Some(enc)) => anyhow::bail!("Unsupported content encoding: {}", enc),
+            _ => anyhow::bail!("No content available for: {}", self.path),
+        }
+    }
 }
 
 /// Type of content item (file, directory, or unknown).
@@ -124,7 +162,9 @@ impl From<String> for ContentType {
 ///
 /// # Implementations
 ///
-/// See `infrastructure::github::GitHubRepository` for the GitHub implementation.
+/// See `infrastructure::github::GitHubRepository` for the GitHub implementation
+/// and `infrastructure::filesystem::FilesystemRepository` for serving a local
+/// checkout of the repository from disk.
 #[async_trait]
 pub trait ContentRepository: Send + Sync {
     /// Retrieve a single file's content or a directory listing.
@@ -166,6 +206,31 @@ pub trait ContentRepository: Send + Sync {
     async fn list_directory(&self, config: &RepoConfig, path: &str)
         -> anyhow::Result<Vec<Content>>;
 
+    /// List every file and directory below a directory, at any depth.
+    ///
+    /// The default implementation walks the tree with
+    /// [`ContentRepository::list_directory`]; backends that can list a whole
+    /// subtree at once override it.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Repository configuration (owner, repo, source)
+    /// * `path` - Path to the directory within the repository
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Vec<Content>)` with all items below the directory, sorted by
+    /// path, with paths relative to the repository root.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if the path doesn't exist or is not a directory
+    /// - Returns error if API rate limit is exceeded
+    /// - Returns error if network communication fails
+    async fn list_tree(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Vec<Content>> {
+        walk_tree(self, config, path).await
+    }
+
     /// Fetch raw file content as JSON directly from a URL.
     ///
     /// Used for aggregation operations where we need to fetch multiple files
@@ -188,6 +253,81 @@ pub trait ContentRepository: Send + Sync {
     async fn get_raw_file(&self, url: &str) -> anyhow::Result<serde_json::Value>;
 }
 
+/// List every item below `path` by walking `repo` one directory at a time.
+///
+/// This is the default [`ContentRepository::list_tree`], available to
+/// implementations that override it but need to fall back to it.
+pub async fn walk_tree<R: ContentRepository + ?Sized>(
+    repo: &R,
+    config: &RepoConfig,
+    path: &str,
+) -> anyhow::Result<Vec<Content>> {
+    let mut items = Vec::new();
+    let mut pending = vec![path.trim_matches('/').to_string()];
+    while let Some(dir) = pending.pop() {
+        for item in repo.list_directory(config, &dir).await? {
+            if item.item_type == ContentType::Dir {
+                pending.push(item.path.clone());
+            }
+            items.push(item);
+        }
+    }
+    items.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(items)
+}
+
+/// A cached value with a soft expiry.
+///
+/// Entries are kept until their hard TTL (the key's expiry) but are only fresh
+/// until `fresh_until`; after that they can still be served while a refresh runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Unix timestamp (seconds) after which the value is stale
+    pub fresh_until: i64,
+    /// Cached value (typically JSON-serialized)
+    pub value: String,
+}
+
+impl CacheEntry {
+    /// Whether the entry is still within its soft TTL.
+    pub fn is_fresh(&self) -> bool {
+        chrono::Utc::now().timestamp() < self.fresh_until
+    }
+}
+
+/// Whether `key` matches a cache key `pattern`, where `*` matches any run of
+/// characters and every other character matches itself.
+pub fn glob_match(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = key.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*`: the pattern must match the whole key
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Uniformly random number in `0..=max`, used to jitter retries and timers.
+///
+/// Falls back to `max` if the system random source is unavailable.
+pub fn random_up_to(max: u64) -> u64 {
+    let mut bytes = [0u8; 8];
+    if SystemRandom::new().fill(&mut bytes).is_err() {
+        return max;
+    }
+    u64::from_le_bytes(bytes) % max.saturating_add(1)
+}
+
 /// Repository trait for caching operations.
 ///
 /// Defines the interface for caching layer (e.g., Redis) to improve
@@ -216,6 +356,26 @@ pub trait CacheRepository: Send + Sync {
     /// - Never errors on cache miss (returns None instead)
     async fn get(&self, key: &str) -> anyhow::Result<Option<String>>;
 
+    /// Retrieve a cached value together with its remaining time-to-live.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Cache key to look up
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some((value, ttl)))` if the key exists, where `ttl` is the
+    /// number of whole seconds before it expires, or `None` when the key has no
+    /// expiry or the backend cannot report it. The default implementation
+    /// never reports a TTL.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if cache connection fails
+    async fn get_with_ttl(&self, key: &str) -> anyhow::Result<Option<(String, Option<u64>)>> {
+        Ok(self.get(key).await?.map(|value| (value, None)))
+    }
+
     /// Store a value in the cache with a TTL.
     ///
     /// # Arguments
@@ -233,4 +393,163 @@ pub trait CacheRepository: Send + Sync {
     /// - Returns error if cache connection fails
     /// - Returns error if the value cannot be stored
     async fn set(&self, key: &str, value: &str, ttl_seconds: u64) -> anyhow::Result<()>;
+
+    /// Take a lock shared by every instance using this cache.
+    ///
+    /// The lock is held until it expires or is released with
+    /// [`CacheRepository::unlock`].
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Lock key
+    /// * `owner` - Identifier of the instance taking the lock (stored as the value)
+    /// * `ttl_seconds` - Time-to-live in seconds before the lock expires
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the lock was taken, `Ok(false)` if another
+    /// instance holds it. Without a shared cache every caller gets the lock.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if the lock command fails or the cache is unreachable;
+    ///   callers must not read this as the lock being held elsewhere
+    async fn try_lock(&self, key: &str, owner: &str, ttl_seconds: u64) -> anyhow::Result<bool>;
+
+    /// Release a lock taken with [`CacheRepository::try_lock`].
+    ///
+    /// The lock is only removed while `owner` still holds it, so a lock that
+    /// expired and was taken by another instance is left alone.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Lock key
+    /// * `owner` - Identifier the lock was taken with
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if the unlock command fails
+    async fn unlock(&self, key: &str, owner: &str) -> anyhow::Result<()>;
+
+    /// Remove a cached key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Cache key to remove
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(true)` if the key existed.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if the delete command fails
+    async fn delete(&self, key: &str) -> anyhow::Result<bool>;
+
+    /// List cached keys matching a pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - Key pattern where `*` matches any run of characters (see [`glob_match`])
+    /// * `limit` - Maximum number of keys returned
+    ///
+    /// # Returns
+    ///
+    /// Returns up to `limit` matching keys, in no particular order.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if cache connection fails
+    async fn scan(&self, pattern: &str, limit: usize) -> anyhow::Result<Vec<String>>;
+
+    /// Retrieve a cached entry stored with [`CacheRepository::set_entry`].
+    ///
+    /// Values stored without an envelope are returned as already stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Cache key to look up
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(Some(CacheEntry))` until the entry's hard TTL expires, and
+    /// `Ok(None)` afterwards.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if cache connection fails
+    async fn get_entry(&self, key: &str) -> anyhow::Result<Option<CacheEntry>> {
+        Ok(self.get(key).await?.map(|raw| {
+            serde_json::from_str::<CacheEntry>(&raw).unwrap_or(CacheEntry {
+                fresh_until: 0,
+                value: raw,
+            })
+        }))
+    }
+
+    /// Store a value with a soft and a hard TTL.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Cache key to store under
+    /// * `value` - Value to cache (typically JSON-serialized)
+    /// * `soft_ttl_seconds` - Seconds the value is considered fresh
+    /// * `hard_ttl_seconds` - Seconds before the key expires (at least `soft_ttl_seconds`)
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if cache connection fails
+    /// - Returns error if the value cannot be stored
+    async fn set_entry(
+        &self,
+        key: &str,
+        value: &str,
+        soft_ttl_seconds: u64,
+        hard_ttl_seconds: u64,
+    ) -> anyhow::Result<()> {
+        let entry = CacheEntry {
+            fresh_until: chrono::Utc::now().timestamp() + soft_ttl_seconds as i64,
+            value: value.to_string(),
+        };
+        let json = serde_json::to_string(&entry)?;
+        self.set(key, &json, hard_ttl_seconds.max(soft_ttl_seconds))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_match_literal_patterns() {
+        assert!(glob_match("v1:catalog", "v1:catalog"));
+        assert!(!glob_match("v1:catalog", "v1:catalog:snapshot"));
+        assert!(!glob_match("v1:catalog:snapshot", "v1:catalog"));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "v1"));
+    }
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "v1:ticker:kaspa:stats"));
+        assert!(glob_match("v1:ticker:*", "v1:ticker:"));
+        assert!(glob_match("v1:ticker:*", "v1:ticker:kaspa:stats:7d"));
+        assert!(!glob_match("v1:ticker:*", "v1:index:kaspa"));
+        assert!(glob_match("*:stats:*", "v1:ticker:kaspa:stats:7d"));
+        assert!(glob_match("v1:*:kaspa:*:7d", "v1:ticker:kaspa:stats:7d"));
+        assert!(!glob_match("v1:*:kaspa:*:7d", "v1:ticker:kaspa:stats:30d"));
+        assert!(glob_match("v1:**:7d", "v1:ticker:7d"));
+    }
+
+    #[test]
+    fn glob_match_does_not_reuse_characters() {
+        // Prefix and suffix may not overlap
+        assert!(!glob_match("ab*ba", "aba"));
+        assert!(glob_match("ab*ba", "abba"));
+        // Each segment is consumed in order
+        assert!(!glob_match("*abc*bc", "xabc"));
+        assert!(glob_match("*abc*bc", "xabcbc"));
+    }
 }
@@ -0,0 +1,223 @@
+//! Cross-exchange price composition.
+//!
+//! Pure functions for combining per-venue quotes into a single reference price:
+//! median-based outlier rejection followed by volume, equal, or median weighting.
+
+use serde::{Deserialize, Serialize};
+
+/// Fewest quotes for which outlier rejection is applied.
+///
+/// With one or two venues the median is not a meaningful reference.
+pub const MIN_QUOTES_FOR_OUTLIERS: usize = 3;
+
+/// How venue prices are combined into a composite price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weighting {
+    /// Weighted by each venue's quote volume (falls back to equal without volumes)
+    #[default]
+    Volume,
+    /// Simple mean of venue prices
+    Equal,
+    /// Median of venue prices
+    Median,
+}
+
+impl Weighting {
+    /// Parse a `weighting` query parameter.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "volume" => Ok(Self::Volume),
+            "equal" => Ok(Self::Equal),
+            "median" => Ok(Self::Median),
+            other => Err(format!(
+                "Invalid weighting: {}. Use: volume, equal, or median",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Volume => "volume",
+            Self::Equal => "equal",
+            Self::Median => "median",
+        }
+    }
+}
+
+/// A single venue's price at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VenueQuote {
+    pub price: f64,
+    /// Quote volume used as the venue's weight
+    pub volume: Option<f64>,
+}
+
+/// Result of combining venue quotes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompositePrice {
+    pub price: f64,
+    /// Quotes that contributed to `price`
+    pub included: usize,
+    /// Quotes dropped as outliers
+    pub outliers: usize,
+}
+
+/// Median of a set of values, ignoring non-finite entries.
+pub fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+/// Whether `price` deviates from `reference` by more than `threshold_pct` percent.
+pub fn is_outlier(price: f64, reference: f64, threshold_pct: f64) -> bool {
+    reference > 0.0 && ((price - reference) / reference).abs() * 100.0 > threshold_pct
+}
+
+/// Combine venue quotes into a composite price.
+///
+/// When at least `MIN_QUOTES_FOR_OUTLIERS` quotes are given, those deviating
+/// from the median by more than `outlier_threshold_pct` percent are dropped
+/// before weighting. Returns `None` when no usable quote remains.
+pub fn composite_price(
+    quotes: &[VenueQuote],
+    weighting: Weighting,
+    outlier_threshold_pct: f64,
+) -> Option<CompositePrice> {
+    let usable: Vec<VenueQuote> = quotes
+        .iter()
+        .copied()
+        .filter(|q| q.price.is_finite() && q.price > 0.0)
+        .collect();
+
+    let kept: Vec<VenueQuote> = if usable.len() >= MIN_QUOTES_FOR_OUTLIERS {
+        let prices: Vec<f64> = usable.iter().map(|q| q.price).collect();
+        let reference = median(&prices)?;
+        usable
+            .iter()
+            .copied()
+            .filter(|q| !is_outlier(q.price, reference, outlier_threshold_pct))
+            .collect()
+    } else {
+        usable.clone()
+    };
+    if kept.is_empty() {
+        return None;
+    }
+
+    let equal = || kept.iter().map(|q| q.price).sum::<f64>() / kept.len() as f64;
+    let price = match weighting {
+        Weighting::Equal => equal(),
+        Weighting::Median => median(&kept.iter().map(|q| q.price).collect::<Vec<_>>())?,
+        Weighting::Volume => {
+            let weighted = kept
+                .iter()
+                .filter_map(|q| q.volume.filter(|v| *v > 0.0).map(|v| (q.price * v, v)))
+                .fold((0.0, 0.0), |(p, w), (qp, qv)| (p + qp, w + qv));
+            if weighted.1 > 0.0 {
+                weighted.0 / weighted.1
+            } else {
+                equal()
+            }
+        }
+    };
+
+    Some(CompositePrice {
+        price,
+        included: kept.len(),
+        outliers: usable.len() - kept.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(price: f64, volume: Option<f64>) -> VenueQuote {
+        VenueQuote { price, volume }
+    }
+
+    #[test]
+    fn median_of_odd_and_even_sets() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), Some(2.5));
+        assert_eq!(median(&[f64::NAN, 5.0, f64::INFINITY]), Some(5.0));
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[f64::NAN]), None);
+    }
+
+    #[test]
+    fn outliers_exceed_the_threshold() {
+        assert!(!is_outlier(110.0, 100.0, 10.0));
+        assert!(is_outlier(110.1, 100.0, 10.0));
+        assert!(is_outlier(89.0, 100.0, 10.0));
+        // No meaningful reference
+        assert!(!is_outlier(5.0, 0.0, 10.0));
+    }
+
+    #[test]
+    fn weighting_parses_known_names() {
+        for weighting in [Weighting::Volume, Weighting::Equal, Weighting::Median] {
+            assert_eq!(Weighting::parse(weighting.as_str()), Ok(weighting));
+        }
+        assert_eq!(Weighting::parse(" median "), Ok(Weighting::Median));
+        assert!(Weighting::parse("vwap").is_err());
+    }
+
+    #[test]
+    fn composite_rejects_outliers_before_weighting() {
+        let quotes = [
+            quote(100.0, Some(1.0)),
+            quote(102.0, Some(3.0)),
+            quote(101.0, None),
+            quote(150.0, Some(100.0)),
+        ];
+        let composite = composite_price(&quotes, Weighting::Volume, 10.0).unwrap();
+        assert_eq!(composite.included, 3);
+        assert_eq!(composite.outliers, 1);
+        // Only venues with volume carry weight
+        assert_eq!(composite.price, (100.0 + 102.0 * 3.0) / 4.0);
+
+        let equal = composite_price(&quotes, Weighting::Equal, 10.0).unwrap();
+        assert_eq!(equal.price, 101.0);
+        let median = composite_price(&quotes, Weighting::Median, 10.0).unwrap();
+        assert_eq!(median.price, 101.0);
+    }
+
+    #[test]
+    fn composite_keeps_small_sets_whole() {
+        // Below MIN_QUOTES_FOR_OUTLIERS nothing is rejected
+        let quotes = [quote(100.0, None), quote(200.0, None)];
+        let composite = composite_price(&quotes, Weighting::Volume, 10.0).unwrap();
+        assert_eq!(composite.included, 2);
+        assert_eq!(composite.outliers, 0);
+        // Without volumes, volume weighting falls back to equal
+        assert_eq!(composite.price, 150.0);
+    }
+
+    #[test]
+    fn composite_ignores_unusable_prices() {
+        let quotes = [
+            quote(0.0, Some(1.0)),
+            quote(-1.0, Some(1.0)),
+            quote(f64::NAN, Some(1.0)),
+        ];
+        assert_eq!(composite_price(&quotes, Weighting::Equal, 10.0), None);
+        assert_eq!(composite_price(&[], Weighting::Median, 10.0), None);
+
+        let quotes = [quote(0.0, Some(1.0)), quote(2.0, Some(0.0))];
+        let composite = composite_price(&quotes, Weighting::Volume, 10.0).unwrap();
+        assert_eq!(composite.price, 2.0);
+        assert_eq!(composite.included, 1);
+    }
+}
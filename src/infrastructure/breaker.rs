@@ -0,0 +1,461 @@
+//! Circuit breaker around upstream content providers.
+//!
+//! `CircuitBreakerRepository` wraps an upstream `ContentRepository` (normally
+//! `GitHubRepository`) and tracks consecutive failures in two circuits per
+//! request: one for the upstream as a whole and one for the repository
+//! (`{upstream}:{owner}/{repo}`). Raw file requests carry no `RepoConfig` and
+//! only use the upstream circuit.
+//!
+//! - **closed**: requests pass; `failure_threshold` consecutive failures open it
+//! - **open**: requests fail immediately with a "Circuit open" error instead of
+//!   waiting through retries; the response cache keeps serving stale entries
+//!   in the meantime
+//! - **half-open**: after `open_secs`, a single probe request is let through;
+//!   success closes the circuit, failure opens it again
+//!
+//! Not-found responses and oversized files are answers, not failures, and do
+//! not count. Circuit states are reported by `/health` and as the
+//! `circuit_breaker_state{circuit}` gauge (0 closed, 1 half-open, 2 open).
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! circuit_breaker:
+//!   enabled: true
+//!   failure_threshold: 5
+//!   open_secs: 30
+//! ```
+
+use crate::domain::{Content, ContentRepository, RepoConfig};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Circuit breaker settings loaded from the `circuit_breaker` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Guard upstream calls with circuit breakers (default: true)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Consecutive failures that open a circuit (default: 5)
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Seconds a circuit stays open before a probe request is let through (default: 30)
+    #[serde(default = "default_open_secs")]
+    pub open_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_failure_threshold() -> u32 {
+    5
+}
+fn default_open_secs() -> u64 {
+    30
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            failure_threshold: default_failure_threshold(),
+            open_secs: default_open_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum State {
+    #[default]
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl State {
+    fn label(self) -> &'static str {
+        match self {
+            State::Closed => "closed",
+            State::Open => "open",
+            State::HalfOpen => "half_open",
+        }
+    }
+
+    fn gauge(self) -> f64 {
+        match self {
+            State::Closed => 0.0,
+            State::HalfOpen => 1.0,
+            State::Open => 2.0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Circuit {
+    state: State,
+    consecutive_failures: u32,
+    /// When the open circuit lets a probe through
+    retry_at: Option<Instant>,
+    /// Start of the probe in flight while half-open
+    probe_started: Option<Instant>,
+    opened_at: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+/// State of one circuit, as reported by `/health`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CircuitStatus {
+    /// Upstream (`github`) or repository (`github:owner/repo`)
+    pub circuit: String,
+    /// `closed`, `open` or `half_open`
+    pub state: String,
+    pub consecutive_failures: u32,
+    /// Time the circuit last opened (ISO 8601)
+    pub opened_at: Option<String>,
+    /// Seconds until an open circuit lets a probe through
+    pub retry_in_secs: Option<u64>,
+    /// Most recent failure
+    pub last_error: Option<String>,
+}
+
+/// Registry of circuits shared by the guarded upstreams.
+pub struct CircuitBreaker {
+    settings: CircuitBreakerConfig,
+    circuits: Mutex<BTreeMap<String, Circuit>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(settings: CircuitBreakerConfig) -> Self {
+        Self {
+            settings,
+            circuits: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn open_duration(&self) -> Duration {
+        Duration::from_secs(self.settings.open_secs.max(1))
+    }
+
+    /// Admit a request through every circuit in `names`, or refuse it if one is open.
+    fn admit(&self, names: &[String]) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let mut circuits = self.circuits.lock().unwrap_or_else(PoisonError::into_inner);
+
+        for name in names {
+            let circuit = circuits.entry(name.clone()).or_default();
+            let waiting = match circuit.state {
+                State::Closed => None,
+                State::Open => circuit.retry_at.filter(|at| *at > now),
+                // A probe that never reported back (e.g. cancelled) is replaced after a while
+                State::HalfOpen => circuit
+                    .probe_started
+                    .map(|started| started + self.open_duration())
+                    .filter(|at| *at > now),
+            };
+            if let Some(at) = waiting {
+                metrics::counter!("circuit_breaker_rejections_total", "circuit" => name.clone())
+                    .increment(1);
+                anyhow::bail!(
+                    "Circuit open for {}: upstream unavailable, retrying in {}s",
+                    name,
+                    at.duration_since(now).as_secs().max(1)
+                );
+            }
+        }
+
+        for name in names {
+            let circuit = circuits.entry(name.clone()).or_default();
+            if circuit.state != State::Closed {
+                circuit.probe_started = Some(now);
+                transition(name, circuit, State::HalfOpen);
+            }
+        }
+        Ok(())
+    }
+
+    /// Record the outcome of an admitted request in every circuit in `names`.
+    fn record(&self, names: &[String], failure: Option<&anyhow::Error>) {
+        let mut circuits = self.circuits.lock().unwrap_or_else(PoisonError::into_inner);
+        for name in names {
+            let circuit = circuits.entry(name.clone()).or_default();
+            circuit.probe_started = None;
+            let Some(error) = failure else {
+                circuit.consecutive_failures = 0;
+                circuit.retry_at = None;
+                transition(name, circuit, State::Closed);
+                continue;
+            };
+
+            circuit.consecutive_failures += 1;
+            circuit.last_error = Some(error.to_string());
+            if circuit.state == State::HalfOpen
+                || circuit.consecutive_failures >= self.settings.failure_threshold
+            {
+                circuit.retry_at = Some(Instant::now() + self.open_duration());
+                if circuit.state != State::Open {
+                    circuit.opened_at = Some(Utc::now());
+                    warn!(
+                        "Circuit {} opened after {} consecutive failures: {}",
+                        name, circuit.consecutive_failures, error
+                    );
+                }
+                transition(name, circuit, State::Open);
+            }
+        }
+    }
+
+    /// State of every circuit that has seen traffic, sorted by name.
+    pub fn status(&self) -> Vec<CircuitStatus> {
+        let now = Instant::now();
+        let circuits = self.circuits.lock().unwrap_or_else(PoisonError::into_inner);
+        circuits
+            .iter()
+            .map(|(name, circuit)| CircuitStatus {
+                circuit: name.clone(),
+                state: circuit.state.label().to_string(),
+                consecutive_failures: circuit.consecutive_failures,
+                opened_at: circuit.opened_at.map(|t| t.to_rfc3339()),
+                retry_in_secs: circuit
+                    .retry_at
+                    .filter(|_| circuit.state == State::Open)
+                    .map(|at| at.saturating_duration_since(now).as_secs()),
+                last_error: circuit.last_error.clone(),
+            })
+            .collect()
+    }
+}
+
+fn transition(name: &str, circuit: &mut Circuit, state: State) {
+    if circuit.state == state {
+        return;
+    }
+    if state == State::Closed {
+        info!("Circuit {} closed", name);
+    }
+    circuit.state = state;
+    metrics::counter!("circuit_breaker_transitions_total", "circuit" => name.to_string(), "state" => state.label())
+        .increment(1);
+    metrics::gauge!("circuit_breaker_state", "circuit" => name.to_string()).set(state.gauge());
+}
+
+/// Whether an error means the upstream is failing, rather than answering that
+/// the requested content is missing or unsuitable.
+fn is_upstream_failure(error: &anyhow::Error) -> bool {
+    let msg = error.to_string();
+    !(msg.contains("404")
+        || msg.contains("Not found")
+        || msg.contains("Not Found")
+        || msg.contains("File too large"))
+}
+
+/// Content repository guarding an upstream with circuit breakers.
+pub struct CircuitBreakerRepository {
+    upstream: String,
+    inner: Arc<dyn ContentRepository>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl CircuitBreakerRepository {
+    /// Guard `inner` with the upstream circuit `upstream` and per-repository circuits.
+    pub fn new(
+        upstream: impl Into<String>,
+        inner: Arc<dyn ContentRepository>,
+        breaker: Arc<CircuitBreaker>,
+    ) -> Self {
+        Self {
+            upstream: upstream.into(),
+            inner,
+            breaker,
+        }
+    }
+
+    async fn guard<T>(
+        &self,
+        config: Option<&RepoConfig>,
+        call: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let mut names = vec![self.upstream.clone()];
+        if let Some(config) = config {
+            names.push(format!(
+                "{}:{}/{}",
+                self.upstream, config.owner, config.repo
+            ));
+        }
+
+        self.breaker.admit(&names)?;
+        let result = call.await;
+        let failure = result.as_ref().err().filter(|e| is_upstream_failure(e));
+        self.breaker.record(&names, failure);
+        result
+    }
+}
+
+#[async_trait]
+impl ContentRepository for CircuitBreakerRepository {
+    async fn get_content(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Content> {
+        self.guard(Some(config), self.inner.get_content(config, path))
+            .await
+    }
+
+    async fn list_directory(
+        &self,
+        config: &RepoConfig,
+        path: &str,
+    ) -> anyhow::Result<Vec<Content>> {
+        self.guard(Some(config), self.inner.list_directory(config, path))
+            .await
+    }
+
+    async fn list_tree(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Vec<Content>> {
+        self.guard(Some(config), self.inner.list_tree(config, path))
+            .await
+    }
+
+    async fn get_raw_file(&self, url: &str) -> anyhow::Result<Value> {
+        self.guard(None, self.inner.get_raw_file(url)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(failure_threshold: u32) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            enabled: true,
+            failure_threshold,
+            open_secs: 30,
+        })
+    }
+
+    fn names() -> Vec<String> {
+        vec!["github".to_string()]
+    }
+
+    fn state(breaker: &CircuitBreaker) -> String {
+        breaker.status()[0].state.clone()
+    }
+
+    /// Let the open period of every circuit run out.
+    fn expire(breaker: &CircuitBreaker) {
+        let past = Instant::now() - Duration::from_secs(1);
+        for circuit in breaker.circuits.lock().unwrap().values_mut() {
+            circuit.retry_at = circuit.retry_at.map(|_| past);
+            circuit.probe_started = circuit
+                .probe_started
+                .map(|_| past - Duration::from_secs(30));
+        }
+    }
+
+    #[test]
+    fn opens_after_consecutive_failures() {
+        let breaker = breaker(3);
+        let error = anyhow::anyhow!("GitHub API error: 502");
+
+        for _ in 0..2 {
+            breaker.admit(&names()).unwrap();
+            breaker.record(&names(), Some(&error));
+        }
+        // A success resets the count
+        breaker.admit(&names()).unwrap();
+        breaker.record(&names(), None);
+        assert_eq!(breaker.status()[0].consecutive_failures, 0);
+
+        for _ in 0..3 {
+            breaker.admit(&names()).unwrap();
+            breaker.record(&names(), Some(&error));
+        }
+        let status = &breaker.status()[0];
+        assert_eq!(status.state, "open");
+        assert_eq!(status.consecutive_failures, 3);
+        assert!(status.opened_at.is_some());
+        assert!(status.retry_in_secs.is_some());
+        assert_eq!(status.last_error.as_deref(), Some("GitHub API error: 502"));
+
+        let rejected = breaker.admit(&names()).unwrap_err();
+        assert!(rejected.to_string().contains("Circuit open for github"));
+    }
+
+    #[test]
+    fn half_open_probe_closes_or_reopens() {
+        let breaker = breaker(1);
+        let error = anyhow::anyhow!("connection refused");
+        breaker.admit(&names()).unwrap();
+        breaker.record(&names(), Some(&error));
+        assert_eq!(state(&breaker), "open");
+
+        // One probe after the open period; others wait for its outcome
+        expire(&breaker);
+        breaker.admit(&names()).unwrap();
+        assert_eq!(state(&breaker), "half_open");
+        assert!(breaker.admit(&names()).is_err());
+
+        // A failed probe opens the circuit again
+        breaker.record(&names(), Some(&error));
+        assert_eq!(state(&breaker), "open");
+        assert!(breaker.admit(&names()).is_err());
+
+        // A successful probe closes it
+        expire(&breaker);
+        breaker.admit(&names()).unwrap();
+        breaker.record(&names(), None);
+        let status = &breaker.status()[0];
+        assert_eq!(status.state, "closed");
+        assert_eq!(status.retry_in_secs, None);
+        breaker.admit(&names()).unwrap();
+    }
+
+    #[test]
+    fn abandoned_probe_is_replaced() {
+        let breaker = breaker(1);
+        breaker.admit(&names()).unwrap();
+        breaker.record(&names(), Some(&anyhow::anyhow!("timeout")));
+        expire(&breaker);
+        breaker.admit(&names()).unwrap();
+        assert!(breaker.admit(&names()).is_err());
+
+        // The probe never reported back
+        expire(&breaker);
+        breaker.admit(&names()).unwrap();
+        assert_eq!(state(&breaker), "half_open");
+    }
+
+    #[test]
+    fn open_circuit_rejects_requests_it_covers() {
+        let breaker = breaker(1);
+        let repo_names = vec!["github".to_string(), "github:o/r".to_string()];
+        breaker.admit(&["github:o/r".to_string()]).unwrap();
+        breaker.record(&["github:o/r".to_string()], Some(&anyhow::anyhow!("500")));
+
+        breaker.admit(&names()).unwrap();
+        breaker.record(&names(), None);
+        assert!(breaker.admit(&repo_names).is_err());
+        // The refused request did not move the upstream circuit
+        assert_eq!(state(&breaker), "closed");
+    }
+
+    #[test]
+    fn missing_content_is_not_a_failure() {
+        assert!(!is_upstream_failure(&anyhow::anyhow!(
+            "GitHub API error: 404 Not Found"
+        )));
+        assert!(!is_upstream_failure(&anyhow::anyhow!("Not found: data/x")));
+        assert!(!is_upstream_failure(&anyhow::anyhow!(
+            "File too large: 200 MiB"
+        )));
+        assert!(is_upstream_failure(&anyhow::anyhow!(
+            "GitHub API error: 503 Service Unavailable"
+        )));
+        assert!(is_upstream_failure(&anyhow::anyhow!(
+            "GitHub rate limit exhausted for all credentials until 2026-10-16T20:00:00+00:00"
+        )));
+    }
+}
@@ -0,0 +1,351 @@
+//! GitHub credential pool with rate-limit-aware rotation.
+//!
+//! Each GitHub credential has its own hourly request budget. `CredentialPool`
+//! holds every configured credential, records the `X-RateLimit-Remaining` and
+//! `X-RateLimit-Reset` headers of each response against the credential that
+//! made the request, and hands out the credential with the most budget left.
+//! A credential whose budget is exhausted is paused until its reset time.
+//!
+//! Credentials come from:
+//! - `GITHUB_TOKEN` and the comma-separated `GITHUB_TOKENS` (personal access tokens)
+//! - a GitHub App installation (`GITHUB_APP_ID`, `GITHUB_APP_INSTALLATION_ID` and
+//!   `GITHUB_APP_PRIVATE_KEY_PATH`), whose short-lived installation token is
+//!   requested with a signed JWT and renewed before it expires
+//!
+//! Without any, requests are unauthenticated.
+//!
+//! # Metrics
+//!
+//! Per credential (labelled `token-1`, `token-2`, …, `app-<installation>` or
+//! `anonymous`; tokens themselves are never exposed):
+//! - `github_credential_remaining` / `github_credential_limit`: last reported budget
+//! - `github_credential_paused`: 1 while the budget is exhausted
+//! - `github_credential_requests_total`: requests made
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::Client;
+use ring::{rand, signature};
+use serde::Deserialize;
+use std::sync::{Mutex, PoisonError};
+use tracing::{info, warn};
+
+/// Installation tokens are renewed this many seconds before they expire.
+const APP_TOKEN_RENEW_SECS: i64 = 300;
+
+/// GitHub App installation whose access tokens are used as a credential.
+pub struct GitHubAppConfig {
+    pub app_id: String,
+    pub installation_id: String,
+    /// RSA private key of the app, in PEM (PKCS#1 or PKCS#8)
+    pub private_key_pem: String,
+}
+
+enum Secret {
+    Anonymous,
+    Token(String),
+    App(Box<AppCredential>),
+}
+
+struct AppCredential {
+    config: GitHubAppConfig,
+    key_pair: signature::RsaKeyPair,
+    /// Current installation token and its expiry
+    token: tokio::sync::Mutex<Option<(String, DateTime<Utc>)>>,
+}
+
+/// Budget last reported by GitHub for one credential.
+#[derive(Default)]
+struct Budget {
+    limit: Option<u64>,
+    remaining: Option<u64>,
+    /// Unix time the budget resets
+    reset_at: Option<i64>,
+}
+
+impl Budget {
+    fn is_paused(&self, now: i64) -> bool {
+        self.remaining == Some(0) && self.reset_at.is_some_and(|reset| reset > now)
+    }
+}
+
+struct Credential {
+    label: String,
+    secret: Secret,
+    budget: Mutex<Budget>,
+}
+
+/// A credential chosen for one request.
+pub struct Lease {
+    index: usize,
+    /// Value of the `Authorization` header, if the request is authenticated
+    pub authorization: Option<String>,
+}
+
+/// Pool of GitHub credentials, rotated by remaining rate-limit budget.
+pub struct CredentialPool {
+    client: Client,
+    credentials: Vec<Credential>,
+}
+
+#[derive(Deserialize)]
+struct InstallationTokenDto {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl CredentialPool {
+    /// Create a pool of personal access tokens.
+    ///
+    /// Without tokens, the pool holds a single anonymous credential.
+    pub fn from_tokens(tokens: Vec<String>) -> Self {
+        let mut credentials: Vec<Credential> = tokens
+            .into_iter()
+            .enumerate()
+            .map(|(i, token)| Credential::new(format!("token-{}", i + 1), Secret::Token(token)))
+            .collect();
+        if credentials.is_empty() {
+            credentials.push(Credential::new("anonymous".to_string(), Secret::Anonymous));
+        }
+        Self {
+            client: Client::new(),
+            credentials,
+        }
+    }
+
+    /// Add a GitHub App installation to the pool, replacing the anonymous credential.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the app's private key cannot be parsed.
+    pub fn with_app(mut self, config: GitHubAppConfig) -> anyhow::Result<Self> {
+        let key_pair = parse_rsa_key(&config.private_key_pem)?;
+        self.credentials
+            .retain(|c| !matches!(c.secret, Secret::Anonymous));
+        self.credentials.push(Credential::new(
+            format!("app-{}", config.installation_id),
+            Secret::App(Box::new(AppCredential {
+                config,
+                key_pair,
+                token: tokio::sync::Mutex::new(None),
+            })),
+        ));
+        Ok(self)
+    }
+
+    /// Number of authenticated credentials.
+    pub fn authenticated_count(&self) -> usize {
+        self.credentials
+            .iter()
+            .filter(|c| !matches!(c.secret, Secret::Anonymous))
+            .count()
+    }
+
+    /// Time until a credential has budget again, or `None` if one has budget now.
+    pub fn wait_for_budget(&self) -> Option<std::time::Duration> {
+        let now = Utc::now().timestamp();
+        let mut earliest_reset: Option<i64> = None;
+        for credential in &self.credentials {
+            let budget = credential.budget();
+            if !budget.is_paused(now) {
+                return None;
+            }
+            if let Some(reset) = budget.reset_at {
+                earliest_reset = Some(earliest_reset.map_or(reset, |e| e.min(reset)));
+            }
+        }
+        earliest_reset.map(|reset| std::time::Duration::from_secs((reset - now).max(1) as u64))
+    }
+
+    /// Pick the credential with the most budget left for the next request.
+    ///
+    /// Credentials GitHub has not reported on yet are preferred, so every
+    /// credential gets measured.
+    ///
+    /// # Errors
+    ///
+    /// - Returns error if every credential is exhausted until its reset time
+    /// - Returns error if a GitHub App installation token cannot be obtained
+    pub async fn acquire(&self) -> anyhow::Result<Lease> {
+        let now = Utc::now().timestamp();
+        let mut best: Option<(usize, u64)> = None;
+        let mut earliest_reset: Option<i64> = None;
+        for (index, credential) in self.credentials.iter().enumerate() {
+            let budget = credential.budget();
+            if budget.is_paused(now) {
+                if let Some(reset) = budget.reset_at {
+                    earliest_reset = Some(earliest_reset.map_or(reset, |e| e.min(reset)));
+                }
+                continue;
+            }
+            let remaining = budget.remaining.unwrap_or(u64::MAX);
+            if best.is_none_or(|(_, most)| remaining > most) {
+                best = Some((index, remaining));
+            }
+        }
+
+        let Some((index, _)) = best else {
+            let reset = earliest_reset
+                .and_then(|t| DateTime::from_timestamp(t, 0))
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default();
+            anyhow::bail!(
+                "GitHub rate limit exhausted for all credentials until {}",
+                reset
+            );
+        };
+
+        let credential = &self.credentials[index];
+        metrics::counter!("github_credential_requests_total", "credential" => credential.label.clone())
+            .increment(1);
+        let authorization = match &credential.secret {
+            Secret::Anonymous => None,
+            Secret::Token(token) => Some(format!("token {}", token)),
+            Secret::App(app) => {
+                let mut token = app.token.lock().await;
+                let renew_after = Utc::now() + chrono::Duration::seconds(APP_TOKEN_RENEW_SECS);
+                if token
+                    .as_ref()
+                    .is_none_or(|(_, expires)| *expires <= renew_after)
+                {
+                    *token = Some(self.installation_token(&app.config, &app.key_pair).await?);
+                }
+                token.as_ref().map(|(t, _)| format!("token {}", t))
+            }
+        };
+        Ok(Lease {
+            index,
+            authorization,
+        })
+    }
+
+    /// Record the rate-limit headers of a response made with `lease`.
+    pub fn record(&self, lease: &Lease, headers: &HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<i64>().ok())
+        };
+        let Some(remaining) = header("x-ratelimit-remaining") else {
+            return;
+        };
+        let credential = &self.credentials[lease.index];
+        let mut budget = credential
+            .budget
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        budget.remaining = Some(remaining.max(0) as u64);
+        budget.limit = header("x-ratelimit-limit").map(|l| l.max(0) as u64);
+        budget.reset_at = header("x-ratelimit-reset");
+
+        if remaining < 100 {
+            warn!(
+                "GitHub API rate limit low for {}: {} requests remaining",
+                credential.label, remaining
+            );
+        }
+        if remaining == 0 {
+            info!(
+                "GitHub API rate limit exceeded for {}, paused until: {:?}",
+                credential.label, budget.reset_at
+            );
+        }
+
+        let label = credential.label.clone();
+        metrics::gauge!("github_credential_remaining", "credential" => label.clone())
+            .set(remaining as f64);
+        if let Some(limit) = budget.limit {
+            metrics::gauge!("github_credential_limit", "credential" => label.clone())
+                .set(limit as f64);
+        }
+        let paused = if budget.is_paused(Utc::now().timestamp()) {
+            1.0
+        } else {
+            0.0
+        };
+        metrics::gauge!("github_credential_paused", "credential" => label).set(paused);
+    }
+
+    /// Exchange a signed app JWT for an installation access token.
+    async fn installation_token(
+        &self,
+        config: &GitHubAppConfig,
+        key_pair: &signature::RsaKeyPair,
+    ) -> anyhow::Result<(String, DateTime<Utc>)> {
+        let jwt = app_jwt(&config.app_id, key_pair)?;
+        let url = format!(
+            "https://api.github.com/app/installations/{}/access_tokens",
+            config.installation_id
+        );
+        let resp = self
+            .client
+            .post(&url)
+            .header("Accept", "application/vnd.github.v3+json")
+            .header("User-Agent", "GitRows-API-Proxy")
+            .header("Authorization", format!("Bearer {}", jwt))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("GitHub App token request failed: {}", resp.status());
+        }
+        let dto: InstallationTokenDto = resp.json().await?;
+        info!(
+            "Obtained GitHub App installation token for installation {}, expires at {}",
+            config.installation_id, dto.expires_at
+        );
+        Ok((dto.token, dto.expires_at))
+    }
+}
+
+impl Credential {
+    fn new(label: String, secret: Secret) -> Self {
+        Self {
+            label,
+            secret,
+            budget: Mutex::new(Budget::default()),
+        }
+    }
+
+    fn budget(&self) -> std::sync::MutexGuard<'_, Budget> {
+        self.budget.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Parse a PEM-encoded RSA private key in PKCS#8 or PKCS#1 form.
+fn parse_rsa_key(pem: &str) -> anyhow::Result<signature::RsaKeyPair> {
+    let body: String = pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    let der = general_purpose::STANDARD.decode(body)?;
+    signature::RsaKeyPair::from_pkcs8(&der)
+        .or_else(|_| signature::RsaKeyPair::from_der(&der))
+        .map_err(|e| anyhow::anyhow!("Invalid GitHub App private key: {}", e))
+}
+
+/// JWT identifying the GitHub App, valid for nine minutes.
+fn app_jwt(app_id: &str, key_pair: &signature::RsaKeyPair) -> anyhow::Result<String> {
+    let now = Utc::now().timestamp();
+    let header = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#);
+    let claims = serde_json::json!({ "iat": now - 60, "exp": now + 540, "iss": app_id });
+    let claims = general_purpose::URL_SAFE_NO_PAD.encode(claims.to_string());
+    let message = format!("{}.{}", header, claims);
+
+    let mut sig = vec![0; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &signature::RSA_PKCS1_SHA256,
+            &rand::SystemRandom::new(),
+            message.as_bytes(),
+            &mut sig,
+        )
+        .map_err(|_| anyhow::anyhow!("Failed to sign GitHub App JWT"))?;
+    Ok(format!(
+        "{}.{}",
+        message,
+        general_purpose::URL_SAFE_NO_PAD.encode(sig)
+    ))
+}
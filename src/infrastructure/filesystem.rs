@@ -0,0 +1,261 @@
+//! Local filesystem content backend.
+//!
+//! This module provides the `FilesystemRepository` implementation of the `ContentRepository`
+//! trait, serving repository content from a directory on disk instead of the GitHub API.
+//!
+//! # Features
+//!
+//! - Serves the `data/` tree mounted into each container (see `docker-compose.yml`)
+//! - No network access and no rate limiting
+//! - Same `Content` shape as the GitHub backend (base64 file bodies, sorted listings)
+//! - Rejects paths that would escape the configured root, including through symlinks
+//!
+//! # Configuration
+//!
+//! Select the backend per entry in `allowed_repos` with `source: local`. The optional
+//! `root` field points at the directory containing the repository tree; when omitted,
+//! the repository's default root is used.
+//!
+//! ```yaml
+//! allowed_repos:
+//!   - source: "local"
+//!     owner: "KaspaDev"
+//!     repo: "Kaspa-Exchange-Data"
+//!     root: "."
+//! ```
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use gatewayapi::infrastructure::FilesystemRepository;
+//! use gatewayapi::domain::{ContentRepository, RepoConfig};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let repo = FilesystemRepository::new(".");
+//!
+//!     let config = RepoConfig {
+//!         source: "local".to_string(),
+//!         owner: "KaspaDev".to_string(),
+//!         repo: "Kaspa-Exchange-Data".to_string(),
+//!         root: None,
+//!     };
+//!
+//!     let tokens = repo.list_directory(&config, "data").await?;
+//!     println!("{} tokens available", tokens.len());
+//!     Ok(())
+//! }
+//! ```
+
+use crate::domain::{Content, ContentRepository, ContentType, RepoConfig};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::Value;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+/// URL scheme used for `Content::url` values produced by this backend.
+pub const FILE_URL_SCHEME: &str = "file://";
+
+/// Content repository backed by a directory on the local filesystem.
+pub struct FilesystemRepository {
+    /// Root used for repositories that do not configure their own `root`
+    default_root: PathBuf,
+    /// Roots configured by individual repositories, also readable by URL
+    roots: Vec<PathBuf>,
+}
+
+impl FilesystemRepository {
+    /// Create a new filesystem repository.
+    ///
+    /// # Arguments
+    ///
+    /// * `default_root` - Directory containing the repository tree, used when a
+    ///   `RepoConfig` does not set `root`
+    pub fn new(default_root: impl Into<PathBuf>) -> Self {
+        Self {
+            default_root: default_root.into(),
+            roots: Vec::new(),
+        }
+    }
+
+    /// Allow `get_raw_file` to read below the `root` of each configured repository.
+    ///
+    /// Files are only served by URL from inside the default root and these roots.
+    pub fn with_roots<P: Into<PathBuf>>(mut self, roots: impl IntoIterator<Item = P>) -> Self {
+        self.roots.extend(roots.into_iter().map(Into::into));
+        self
+    }
+
+    /// Resolve a repository-relative path to a location on disk.
+    ///
+    /// Only normal path components are accepted, so requests cannot escape the
+    /// configured root via `..`, absolute paths or drive prefixes, and the
+    /// canonical path must still lie inside the root once symlinks are followed.
+    async fn resolve(&self, config: &RepoConfig, path: &str) -> anyhow::Result<PathBuf> {
+        let root = config
+            .root
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| self.default_root.clone());
+
+        let relative = Path::new(path.trim_start_matches('/'));
+        let mut resolved = root.clone();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => resolved.push(part),
+                Component::CurDir => {}
+                _ => anyhow::bail!("Invalid path: {}", path),
+            }
+        }
+        Self::confine(&resolved, &[root.as_path()], path).await
+    }
+
+    /// Canonicalize `path` and check that it lies inside one of `roots`.
+    ///
+    /// Both sides are canonicalized, so a symlink pointing out of a root is rejected.
+    async fn confine(path: &Path, roots: &[&Path], display: &str) -> anyhow::Result<PathBuf> {
+        let canonical = tokio::fs::canonicalize(path)
+            .await
+            .map_err(|e| Self::map_io_error(e, display))?;
+        for root in roots {
+            if let Ok(root) = tokio::fs::canonicalize(root).await {
+                if canonical.starts_with(&root) {
+                    return Ok(canonical);
+                }
+            }
+        }
+        anyhow::bail!("Invalid path: {}", display)
+    }
+
+    /// Build the `file://` URL for a path on disk.
+    fn file_url(path: &Path) -> String {
+        let absolute = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        format!("{}{}", FILE_URL_SCHEME, absolute.display())
+    }
+
+    /// Map I/O errors onto the messages the API layer translates into status codes.
+    fn map_io_error(err: std::io::Error, path: &str) -> anyhow::Error {
+        if err.kind() == ErrorKind::NotFound {
+            anyhow::anyhow!("Not found: {}", path)
+        } else {
+            anyhow::anyhow!("Filesystem error for {}: {}", path, err)
+        }
+    }
+}
+
+#[async_trait]
+impl ContentRepository for FilesystemRepository {
+    async fn get_content(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Content> {
+        let full_path = self.resolve(config, path).await?;
+        let metadata = tokio::fs::metadata(&full_path)
+            .await
+            .map_err(|e| Self::map_io_error(e, path))?;
+
+        if !metadata.is_file() {
+            anyhow::bail!("Not a file: {}", path);
+        }
+
+        let bytes = tokio::fs::read(&full_path)
+            .await
+            .map_err(|e| Self::map_io_error(e, path))?;
+
+        let name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Ok(Content {
+            name,
+            path: path.trim_start_matches('/').to_string(),
+            item_type: ContentType::File,
+            size: Some(bytes.len() as u64),
+            content: Some(general_purpose::STANDARD.encode(bytes)),
+            encoding: Some("base64".to_string()),
+            html_url: None,
+            download_url: None,
+            url: Self::file_url(&full_path),
+        })
+    }
+
+    async fn list_directory(
+        &self,
+        config: &RepoConfig,
+        path: &str,
+    ) -> anyhow::Result<Vec<Content>> {
+        let full_path = self.resolve(config, path).await?;
+        let mut entries = tokio::fs::read_dir(&full_path)
+            .await
+            .map_err(|e| Self::map_io_error(e, path))?;
+
+        let base = path.trim_matches('/');
+        let mut items = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| Self::map_io_error(e, path))?
+        {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+
+            let file_type = entry
+                .file_type()
+                .await
+                .map_err(|e| Self::map_io_error(e, path))?;
+            let item_type = if file_type.is_dir() {
+                ContentType::Dir
+            } else if file_type.is_file() {
+                ContentType::File
+            } else {
+                ContentType::Unknown
+            };
+            let size = if item_type == ContentType::File {
+                entry.metadata().await.ok().map(|m| m.len())
+            } else {
+                None
+            };
+
+            let item_path = if base.is_empty() {
+                name.clone()
+            } else {
+                format!("{}/{}", base, name)
+            };
+
+            items.push(Content {
+                name,
+                path: item_path,
+                item_type,
+                content: None,
+                encoding: None,
+                html_url: None,
+                download_url: None,
+                url: Self::file_url(&entry.path()),
+                size,
+            });
+        }
+
+        // Match the GitHub contents API, which returns entries sorted by name
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(items)
+    }
+
+    async fn get_raw_file(&self, url: &str) -> anyhow::Result<Value> {
+        let Some(path) = url.strip_prefix(FILE_URL_SCHEME) else {
+            anyhow::bail!("Unsupported URL for filesystem backend: {}", url);
+        };
+
+        let roots: Vec<&Path> = std::iter::once(&self.default_root)
+            .chain(&self.roots)
+            .map(PathBuf::as_path)
+            .collect();
+        let full_path = Self::confine(Path::new(path), &roots, path).await?;
+
+        let bytes = tokio::fs::read(&full_path)
+            .await
+            .map_err(|e| Self::map_io_error(e, path))?;
+        let val: Value = serde_json::from_slice(&bytes)?;
+        Ok(val)
+    }
+}
@@ -9,14 +9,35 @@
 //! - Request timeouts (30s for requests, 5s for connections)
 //! - Detailed logging of rate limit status
 //! - Support for file content, directory listings, and raw file access
+//! - Whole-subtree listings in one request through the Git Trees API
+//! - Conditional requests: ETags are stored with response bodies in the cache and sent as
+//!   `If-None-Match`, so revalidating an unchanged file costs no rate limit
+//! - Files over the contents API's 1 MB inline limit are downloaded separately, streamed
+//!   and bounded by `max_file_bytes`, and kept in the cache under their blob SHA so an
+//!   unchanged file is not downloaded again
 //!
 //! # Rate Limiting
 //!
-//! GitHub's authenticated API allows 5,000 requests per hour. This implementation:
-//! - Monitors `X-RateLimit-Remaining` header
+//! GitHub's authenticated API allows 5,000 requests per hour per credential. This implementation:
+//! - Spreads requests over a pool of credentials (see [`crate::infrastructure::credentials`]),
+//!   choosing the one with the most `X-RateLimit-Remaining` and pausing exhausted ones
 //! - Logs warnings when < 100 requests remaining
-//! - Automatically retries on 429/403 status codes with exponential backoff
-//! - Respects `Retry-After` header when provided
+//! - Retries rate-limited, 5xx and failed requests with jittered exponential backoff (see
+//!   [`crate::infrastructure::retry`]), on another credential when one is available
+//! - Respects `Retry-After` header when provided, and waits for `X-RateLimit-Reset` when
+//!   every credential is exhausted
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! github:
+//!   conditional_requests: true
+//!   etag_ttl_secs: 604800
+//!   max_file_bytes: 104857600
+//!   retry:
+//!     max_attempts: 4
+//!     deadline_secs: 20
+//! ```
 //!
 //! # Examples
 //!
@@ -26,13 +47,14 @@
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
-//!     let token = std::env::var("GITHUB_TOKEN")?;
+//!     let token = std::env::var("GITHUB_TOKEN").ok();
 //!     let repo = GitHubRepository::new(token);
 //!     
 //!     let config = RepoConfig {
 //!         source: "github".to_string(),
 //!         owner: "KaspaDev".to_string(),
 //!         repo: "Kaspa-Exchange-Data".to_string(),
+//!         root: None,
 //!     };
 //!     
 //!     let content = repo.get_content(&config, "README.md").await?;
@@ -41,14 +63,99 @@
 //! }
 //! ```
 
-use crate::domain::{Content, ContentRepository, ContentType, RepoConfig};
+use crate::domain::{
+    walk_tree, CacheRepository, Content, ContentRepository, ContentType, RepoConfig,
+};
+use crate::infrastructure::credentials::CredentialPool;
+use crate::infrastructure::retry::{retry_after, RetryConfig};
 use async_trait::async_trait;
-use reqwest::{Client, Response};
-use serde::Deserialize;
+use base64::{engine::general_purpose, Engine as _};
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+/// GitHub client settings loaded from the `github` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitHubConfig {
+    /// Revalidate responses with their stored ETag instead of re-downloading them (default: true)
+    #[serde(default = "default_conditional_requests")]
+    pub conditional_requests: bool,
+    /// Seconds an ETag and its response body are kept for revalidation (default: 604800)
+    #[serde(default = "default_etag_ttl_secs")]
+    pub etag_ttl_secs: u64,
+    /// Largest file or response body downloaded, in bytes (default: 100 MiB)
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Retry policy of GitHub requests
+    #[serde(default)]
+    pub retry: RetryConfig,
+}
+
+fn default_conditional_requests() -> bool {
+    true
+}
+fn default_etag_ttl_secs() -> u64 {
+    7 * 24 * 3600
+}
+fn default_max_file_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            conditional_requests: default_conditional_requests(),
+            etag_ttl_secs: default_etag_ttl_secs(),
+            max_file_bytes: default_max_file_bytes(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+/// Representation requested from the contents API.
+#[derive(Clone, Copy)]
+enum Media {
+    /// JSON metadata, with base64 file content
+    Json,
+    /// The file itself
+    Raw,
+}
+
+impl Media {
+    fn accept(self) -> &'static str {
+        match self {
+            Media::Json => "application/vnd.github.v3+json",
+            Media::Raw => "application/vnd.github.v3.raw",
+        }
+    }
+
+    fn etag_key(self, url: &str) -> String {
+        let kind = match self {
+            Media::Json => "json",
+            Media::Raw => "raw",
+        };
+        format!("v1:etag:{}:{}", kind, url)
+    }
+
+    fn error_label(self) -> &'static str {
+        match self {
+            Media::Json => "GitHub API Error",
+            Media::Raw => "GitHub Fetch Error",
+        }
+    }
+}
+
+/// A response body stored with the ETag GitHub served it with.
+#[derive(Serialize, Deserialize)]
+struct EtagEntry {
+    etag: String,
+    body: String,
+}
+
 /// GitHub API client with automatic rate limit handling and retry logic.
 ///
 /// This struct implements the `ContentRepository` trait for accessing GitHub repositories.
@@ -57,10 +164,15 @@ use tracing::{info, warn};
 pub struct GitHubRepository {
     /// HTTP client configured with timeouts
     client: Client,
-    /// GitHub personal access token for authentication (optional)
-    /// If None, requests are made without authentication (60 req/hour limit for public repos)
-    /// If Some, requests use authentication (5,000 req/hour limit)
-    token: Option<String>,
+    /// Credentials requests are authenticated with, chosen per request by remaining budget
+    /// (a single anonymous credential allows 60 req/hour for public repos)
+    credentials: CredentialPool,
+    /// Store of ETags and bodies for conditional requests, with their TTL in seconds
+    etag_cache: Option<(Arc<dyn CacheRepository>, u64)>,
+    /// Largest body read from a response, in bytes
+    max_file_bytes: u64,
+    /// When and how failed requests are retried
+    retry: RetryConfig,
 }
 
 impl GitHubRepository {
@@ -98,111 +210,330 @@ impl GitHubRepository {
             .build()
             .expect("Failed to build HTTP client");
 
-        Self { client, token }
+        Self {
+            client,
+            credentials: CredentialPool::from_tokens(token.into_iter().collect()),
+            etag_cache: None,
+            max_file_bytes: default_max_file_bytes(),
+            retry: RetryConfig::default(),
+        }
     }
 
-    /// Check and log rate limit information from response headers.
-    ///
-    /// Monitors the `X-RateLimit-Remaining` header and logs warnings when
-    /// rate limits are low or exceeded.
+    /// Retry failed requests according to `retry` instead of the default policy.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Authenticate requests with a pool of credentials instead of a single token.
+    pub fn with_credentials(mut self, credentials: CredentialPool) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Limit the size of downloaded files and response bodies.
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self
+    }
+
+    /// Enable conditional requests, keeping ETags and bodies in `cache_repo`.
     ///
     /// # Arguments
     ///
-    /// * `resp` - HTTP response from GitHub API
-    fn check_rate_limit(&self, resp: &Response) {
-        if let Some(remaining) = resp.headers().get("x-ratelimit-remaining") {
-            if let Ok(remaining_str) = remaining.to_str() {
-                if let Ok(remaining_count) = remaining_str.parse::<u32>() {
-                    if remaining_count < 100 {
-                        warn!(
-                            "GitHub API rate limit low: {} requests remaining",
-                            remaining_count
-                        );
-                    }
-                    if remaining_count == 0 {
-                        if let Some(reset) = resp.headers().get("x-ratelimit-reset") {
-                            if let Ok(reset_str) = reset.to_str() {
-                                info!("GitHub API rate limit exceeded, resets at: {}", reset_str);
-                            }
-                        }
-                    }
+    /// * `cache_repo` - Cache holding the last body and ETag of each URL
+    /// * `ttl_seconds` - How long an ETag is kept for revalidation
+    pub fn with_etag_cache(
+        mut self,
+        cache_repo: Arc<dyn CacheRepository>,
+        ttl_seconds: u64,
+    ) -> Self {
+        self.etag_cache = Some((cache_repo, ttl_seconds));
+        self
+    }
+
+    /// Fetch `url` as `media`, returning the response body.
+    ///
+    /// With an ETag cache, the stored ETag is sent as `If-None-Match` and a
+    /// `304 Not Modified` answer is served from the stored body.
+    async fn fetch(&self, url: &str, media: Media) -> anyhow::Result<String> {
+        let etag_key = media.etag_key(url);
+        let cached = match &self.etag_cache {
+            Some((cache_repo, _)) => cache_repo
+                .get(&etag_key)
+                .await
+                .ok()
+                .flatten()
+                .and_then(|raw| serde_json::from_str::<EtagEntry>(&raw).ok()),
+            None => None,
+        };
+
+        let resp = self
+            .execute_with_retry(|authorization| {
+                let mut request = self
+                    .client
+                    .get(url)
+                    .header("Accept", media.accept())
+                    .header("User-Agent", "GitRows-API-Proxy");
+
+                // Add Authorization header only if the credential has one
+                if let Some(authorization) = authorization {
+                    request = request.header("Authorization", authorization);
+                }
+                if let Some(entry) = &cached {
+                    request = request.header(IF_NONE_MATCH, &entry.etag);
                 }
+
+                request.send()
+            })
+            .await?;
+
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                metrics::counter!("github_conditional_requests_total", "result" => "not_modified")
+                    .increment(1);
+                return Ok(entry.body);
             }
         }
+        if !resp.status().is_success() {
+            anyhow::bail!("{}: {}", media.error_label(), resp.status());
+        }
+
+        let etag = resp
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let body = String::from_utf8(self.read_body(resp, url).await?)?;
+
+        if let (Some((cache_repo, ttl)), Some(etag)) = (&self.etag_cache, etag) {
+            let result = if cached.is_some() {
+                "modified"
+            } else {
+                "unconditional"
+            };
+            metrics::counter!("github_conditional_requests_total", "result" => result).increment(1);
+            let entry = EtagEntry {
+                etag,
+                body: body.clone(),
+            };
+            if let Ok(json) = serde_json::to_string(&entry) {
+                let _ = cache_repo.set(&etag_key, &json, *ttl).await;
+            }
+        }
+        Ok(body)
     }
 
-    /// Execute GitHub API request with exponential backoff retry on rate limits.
+    /// Read a response body chunk by chunk, failing once it exceeds `max_file_bytes`.
+    async fn read_body(&self, mut resp: Response, url: &str) -> anyhow::Result<Vec<u8>> {
+        let too_large = |size: u64| {
+            anyhow::anyhow!(
+                "File too large: {} is over {} bytes (max: {})",
+                url,
+                size,
+                self.max_file_bytes
+            )
+        };
+        if let Some(length) = resp.content_length() {
+            if length > self.max_file_bytes {
+                return Err(too_large(length));
+            }
+        }
+
+        let mut body = Vec::with_capacity(resp.content_length().unwrap_or(0) as usize);
+        while let Some(chunk) = resp.chunk().await? {
+            if (body.len() + chunk.len()) as u64 > self.max_file_bytes {
+                return Err(too_large(self.max_file_bytes));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Base64 content of a file the contents API left out.
     ///
-    /// Automatically retries requests that fail due to rate limiting (status 429 or 403).
-    /// Uses exponential backoff starting at 100ms, doubling on each retry, capped at 30 seconds.
+    /// A blob's content is fixed by its SHA, so with an ETag cache the content
+    /// is kept under the SHA for `etag_ttl_secs`. A file whose metadata
+    /// revalidated with `304 Not Modified` then costs no download.
+    async fn large_file_content(
+        &self,
+        config: &RepoConfig,
+        dto: &GitHubItemDto,
+    ) -> anyhow::Result<String> {
+        let blob_cache = self
+            .etag_cache
+            .as_ref()
+            .zip(dto.sha.as_deref())
+            .map(|((cache_repo, ttl), sha)| (cache_repo, *ttl, format!("v1:blob:{}", sha)));
+        if let Some((cache_repo, _, key)) = &blob_cache {
+            if let Ok(Some(content)) = cache_repo.get(key).await {
+                metrics::counter!("github_large_file_downloads_total", "via" => "cache")
+                    .increment(1);
+                return Ok(content);
+            }
+        }
+
+        let bytes = self.download_large_file(config, dto).await?;
+        let content = general_purpose::STANDARD.encode(bytes);
+        if let Some((cache_repo, ttl, key)) = &blob_cache {
+            let _ = cache_repo.set(key, &content, *ttl).await;
+        }
+        Ok(content)
+    }
+
+    /// Download a file whose content the contents API left out.
+    ///
+    /// The contents API inlines files up to 1 MB only; larger ones come back
+    /// with an empty `content`. They are read from `download_url` when GitHub
+    /// provides one, and from the Git blobs API otherwise.
+    async fn download_large_file(
+        &self,
+        config: &RepoConfig,
+        dto: &GitHubItemDto,
+    ) -> anyhow::Result<Vec<u8>> {
+        if let Some(size) = dto.size.filter(|size| *size > self.max_file_bytes) {
+            anyhow::bail!(
+                "File too large: {} is {} bytes (max: {})",
+                dto.path,
+                size,
+                self.max_file_bytes
+            );
+        }
+        let (url, via) = match (&dto.download_url, &dto.sha) {
+            (Some(url), _) => (url.clone(), "download_url"),
+            (None, Some(sha)) => (
+                format!(
+                    "https://api.github.com/repos/{}/{}/git/blobs/{}",
+                    config.owner, config.repo, sha
+                ),
+                "blob",
+            ),
+            (None, None) => anyhow::bail!("No content available for: {}", dto.path),
+        };
+        info!(
+            "Downloading large file {} ({} bytes) via {}",
+            dto.path,
+            dto.size.unwrap_or(0),
+            via
+        );
+        metrics::counter!("github_large_file_downloads_total", "via" => via).increment(1);
+
+        let resp = self
+            .execute_with_retry(|authorization| {
+                let mut request = self
+                    .client
+                    .get(&url)
+                    .header("Accept", Media::Raw.accept())
+                    .header("User-Agent", "GitRows-API-Proxy");
+                if let Some(authorization) = authorization {
+                    request = request.header("Authorization", authorization);
+                }
+                request.send()
+            })
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("{}: {}", Media::Raw.error_label(), resp.status());
+        }
+        self.read_body(resp, &url).await
+    }
+
+    /// Execute GitHub API request, retrying transient failures according to the retry policy.
     ///
     /// # Arguments
     ///
-    /// * `operation` - Closure that creates and sends the HTTP request
+    /// * `operation` - Closure that creates and sends the HTTP request with the given
+    ///   `Authorization` header value
     ///
     /// # Returns
     ///
-    /// Returns the successful response or an error after all retries exhausted.
+    /// Returns the last response, successful or not, once it is not retried.
     ///
     /// # Retry Strategy
     ///
-    /// - Maximum 5 retry attempts
-    /// - Exponential backoff: 100ms → 200ms → 400ms → 800ms → 1.6s (capped at 30s)
-    /// - Respects `Retry-After` header if present
-    /// - Logs each retry attempt with wait time
+    /// - Up to `max_attempts` attempts, with no retry started after `deadline_secs`
+    /// - Retries connection errors, timeouts and the configured statuses (by default 429 and 5xx)
+    /// - Retries a 403 only when it reports a rate limit (`X-RateLimit-Remaining: 0` or
+    ///   `Retry-After`), not when access is denied
+    /// - Waits for `Retry-After` when present; when every credential is exhausted, waits
+    ///   until the earliest `X-RateLimit-Reset`; otherwise uses jittered exponential backoff
+    /// - Each attempt uses the credential with the most budget left
+    /// - Counts retries in `github_retries_total{reason}`
     ///
     /// # Errors
     ///
     /// Returns error if:
-    /// - Network request fails
-    /// - Rate limit exceeded after all retries
-    /// - Server returns non-retryable error
+    /// - Network request fails on the last attempt, or with a non-transient error
+    /// - Every credential is exhausted
     async fn execute_with_retry<F, Fut>(&self, mut operation: F) -> anyhow::Result<Response>
     where
-        F: FnMut() -> Fut,
+        F: FnMut(Option<String>) -> Fut,
         Fut: std::future::Future<Output = Result<Response, reqwest::Error>>,
     {
-        let max_retries = 5;
-        let mut delay_ms = 100;
-
-        for attempt in 0..max_retries {
-            let resp = operation().await?;
-
-            // Check rate limit headers
-            self.check_rate_limit(&resp);
-
-            // If we hit rate limit and have retries left, retry
-            let status = resp.status().as_u16();
-            if (status == 429 || status == 403) && attempt < max_retries - 1 {
-                // Check for Retry-After header
-                let wait_time = if let Some(retry_after) = resp.headers().get("retry-after") {
-                    if let Ok(retry_str) = retry_after.to_str() {
-                        retry_str.parse::<u64>().unwrap_or(delay_ms / 1000)
-                    } else {
-                        delay_ms / 1000
-                    }
-                } else {
-                    delay_ms / 1000
-                };
+        let started = Instant::now();
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 0;
 
-                warn!(
-                    "Rate limited (attempt {}/{}), waiting {} seconds before retry",
-                    attempt + 1,
-                    max_retries,
-                    wait_time
-                );
-                tokio::time::sleep(Duration::from_secs(wait_time)).await;
-
-                // Exponential backoff
-                delay_ms = (delay_ms * 2).min(30000); // Cap at 30 seconds
-                continue;
-            }
+        loop {
+            attempt += 1;
+            let lease = self.credentials.acquire().await?;
+            let outcome = operation(lease.authorization.clone()).await;
 
-            // Success or final attempt
-            return Ok(resp);
+            let retry = match &outcome {
+                Ok(resp) => {
+                    // Record the credential's remaining budget
+                    self.credentials.record(&lease, resp.headers());
+                    self.retry_delay(resp, attempt)
+                }
+                Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                    Some(("transport", self.retry.backoff(attempt)))
+                }
+                Err(_) => None,
+            };
+            let Some((reason, wait)) = retry.filter(|(_, wait)| {
+                attempt < max_attempts && started.elapsed() + *wait <= self.retry.deadline()
+            }) else {
+                return Ok(outcome?);
+            };
+
+            warn!(
+                "GitHub request failed ({}, attempt {}/{}), retrying in {}ms",
+                match &outcome {
+                    Ok(resp) => resp.status().to_string(),
+                    Err(e) => e.to_string(),
+                },
+                attempt,
+                max_attempts,
+                wait.as_millis()
+            );
+            metrics::counter!("github_retries_total", "reason" => reason).increment(1);
+            tokio::time::sleep(wait).await;
         }
+    }
+
+    /// Reason and wait before retrying `resp`, or `None` if it is final.
+    fn retry_delay(&self, resp: &Response, attempt: u32) -> Option<(&'static str, Duration)> {
+        let status = resp.status().as_u16();
+        let retry_after = retry_after(resp.headers());
+        let exhausted = resp
+            .headers()
+            .get("x-ratelimit-remaining")
+            .is_some_and(|v| v.as_bytes() == b"0");
 
-        anyhow::bail!("GitHub API request failed after {} retries", max_retries)
+        let reason = match status {
+            403 if exhausted || retry_after.is_some() => "rate_limit",
+            429 if self.retry.retries_status(status) => "rate_limit",
+            _ if self.retry.retries_status(status) => "status",
+            _ => return None,
+        };
+        let wait = match retry_after {
+            Some(wait) => wait,
+            None if exhausted => self
+                .credentials
+                .wait_for_budget()
+                .unwrap_or_else(|| self.retry.backoff(attempt)),
+            None => self.retry.backoff(attempt),
+        };
+        Some((reason, wait))
     }
 }
 
@@ -220,6 +551,17 @@ struct GitHubItemDto {
     encoding: Option<String>,
     html_url: Option<String>,
     download_url: Option<String>,
+    size: Option<u64>,
+    sha: Option<String>,
+}
+
+impl GitHubItemDto {
+    /// A file whose content the contents API did not inline (over 1 MB).
+    fn is_missing_content(&self) -> bool {
+        self.item_type == "file"
+            && (self.content.as_deref().is_none_or(str::is_empty)
+                || self.encoding.as_deref() != Some("base64"))
+    }
 }
 
 impl From<GitHubItemDto> for Content {
@@ -233,40 +575,48 @@ impl From<GitHubItemDto> for Content {
             html_url: dto.html_url,
             download_url: dto.download_url,
             url: dto.url,
+            size: dto.size,
         }
     }
 }
 
+/// Git Trees API response.
+#[derive(Deserialize)]
+struct GitTreeDto {
+    tree: Vec<GitTreeEntryDto>,
+    /// The listing exceeded GitHub's limits and is incomplete
+    #[serde(default)]
+    truncated: bool,
+}
+
+/// Entry of a Git Trees API response; `path` is relative to the listed tree.
+#[derive(Deserialize)]
+struct GitTreeEntryDto {
+    path: String,
+    #[serde(rename = "type")]
+    entry_type: String,
+    size: Option<u64>,
+}
+
+fn contents_url(config: &RepoConfig, path: &str) -> String {
+    format!(
+        "https://api.github.com/repos/{}/{}/contents/{}",
+        config.owner, config.repo, path
+    )
+}
+
 #[async_trait]
 impl ContentRepository for GitHubRepository {
     async fn get_content(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Content> {
         let clean_path = path.trim_start_matches('/');
-        let url = format!(
-            "https://api.github.com/repos/{}/{}/contents/{}",
-            config.owner, config.repo, clean_path
-        );
-
-        let resp = self
-            .execute_with_retry(|| {
-                let mut request = self.client
-                    .get(&url)
-                    .header("Accept", "application/vnd.github.v3+json")
-                    .header("User-Agent", "GitRows-API-Proxy");
-                
-                // Add Authorization header only if token is provided
-                if let Some(ref token) = self.token {
-                    request = request.header("Authorization", format!("token {}", token));
-                }
-                
-                request.send()
-            })
-            .await?;
+        let url = contents_url(config, clean_path);
 
-        if !resp.status().is_success() {
-            anyhow::bail!("GitHub API Error: {}", resp.status());
+        let body = self.fetch(&url, Media::Json).await?;
+        let mut dto: GitHubItemDto = serde_json::from_str(&body)?;
+        if dto.is_missing_content() {
+            dto.content = Some(self.large_file_content(config, &dto).await?);
+            dto.encoding = Some("base64".to_string());
         }
-
-        let dto: GitHubItemDto = resp.json().await?;
         Ok(Content::from(dto))
     }
 
@@ -276,57 +626,75 @@ impl ContentRepository for GitHubRepository {
         path: &str,
     ) -> anyhow::Result<Vec<Content>> {
         let clean_path = path.trim_start_matches('/');
+        let url = contents_url(config, clean_path);
+
+        let body = self.fetch(&url, Media::Json).await?;
+        let dtos: Vec<GitHubItemDto> = serde_json::from_str(&body)?;
+        Ok(dtos.into_iter().map(Content::from).collect())
+    }
+
+    async fn list_tree(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Vec<Content>> {
+        let base = path.trim_matches('/');
+        let tree_ish = if base.is_empty() {
+            "HEAD".to_string()
+        } else {
+            format!("HEAD:{}", base)
+        };
         let url = format!(
-            "https://api.github.com/repos/{}/{}/contents/{}",
-            config.owner, config.repo, clean_path
+            "https://api.github.com/repos/{}/{}/git/trees/{}?recursive=1",
+            config.owner, config.repo, tree_ish
         );
 
-        let resp = self
-            .execute_with_retry(|| {
-                let mut request = self.client
-                    .get(&url)
-                    .header("Accept", "application/vnd.github.v3+json")
-                    .header("User-Agent", "GitRows-API-Proxy");
-                
-                // Add Authorization header only if token is provided
-                if let Some(ref token) = self.token {
-                    request = request.header("Authorization", format!("token {}", token));
-                }
-                
-                request.send()
-            })
-            .await?;
-
-        if !resp.status().is_success() {
-            anyhow::bail!("GitHub API Error: {}", resp.status());
+        let body = self.fetch(&url, Media::Json).await?;
+        let dto: GitTreeDto = serde_json::from_str(&body)?;
+        if dto.truncated {
+            warn!(
+                "Git tree of {}/{}:{} truncated, listing directories instead",
+                config.owner, config.repo, base
+            );
+            return walk_tree(self, config, base).await;
         }
 
-        let dtos: Vec<GitHubItemDto> = resp.json().await?;
-        Ok(dtos.into_iter().map(Content::from).collect())
-    }
-
-    async fn get_raw_file(&self, url: &str) -> anyhow::Result<Value> {
-        let resp = self
-            .execute_with_retry(|| {
-                let mut request = self.client
-                    .get(url)
-                    .header("Accept", "application/vnd.github.v3.raw")
-                    .header("User-Agent", "GitRows-API-Proxy");
-                
-                // Add Authorization header only if token is provided
-                if let Some(ref token) = self.token {
-                    request = request.header("Authorization", format!("token {}", token));
+        let mut items: Vec<Content> = dto
+            .tree
+            .into_iter()
+            .map(|entry| {
+                let full_path = if base.is_empty() {
+                    entry.path.clone()
+                } else {
+                    format!("{}/{}", base, entry.path)
+                };
+                let name = entry
+                    .path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                let item_type = match entry.entry_type.as_str() {
+                    "blob" => ContentType::File,
+                    "tree" => ContentType::Dir,
+                    _ => ContentType::Unknown,
+                };
+                Content {
+                    name,
+                    url: contents_url(config, &full_path),
+                    path: full_path,
+                    item_type,
+                    content: None,
+                    encoding: None,
+                    html_url: None,
+                    download_url: None,
+                    size: entry.size,
                 }
-                
-                request.send()
             })
-            .await?;
-
-        if !resp.status().is_success() {
-            anyhow::bail!("GitHub Fetch Error: {}", resp.status());
-        }
+            .collect();
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(items)
+    }
 
-        let val: Value = resp.json().await?;
+    async fn get_raw_file(&self, url: &str) -> anyhow::Result<Value> {
+        let body = self.fetch(url, Media::Raw).await?;
+        let val: Value = serde_json::from_str(&body)?;
         Ok(val)
     }
 }
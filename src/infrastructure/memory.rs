@@ -0,0 +1,282 @@
+//! In-process cache tier.
+//!
+//! `MemoryCacheRepository` keeps entries in a least-recently-used map bounded
+//! by the total size of keys and values. Entries also expire after their TTL.
+//! It can be used on its own (e.g. in development without `REDIS_URL`) or as
+//! the first tier in front of Redis (see [`crate::infrastructure::tiered`]).
+
+use crate::domain::{glob_match, CacheRepository};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Memory tier settings loaded from the `memory_cache` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MemoryCacheConfig {
+    /// Keep an in-process cache tier (default: true)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Upper bound on the size of cached keys and values, in bytes (default: 64 MiB)
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+    /// Longest time, in seconds, an entry is kept in memory when Redis is also
+    /// configured, so changes made by other replicas are picked up (default: 60)
+    #[serde(default = "default_max_ttl_secs")]
+    pub max_ttl_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_max_bytes() -> usize {
+    64 * 1024 * 1024
+}
+fn default_max_ttl_secs() -> u64 {
+    60
+}
+
+impl Default for MemoryCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            max_bytes: default_max_bytes(),
+            max_ttl_secs: default_max_ttl_secs(),
+        }
+    }
+}
+
+struct Slot {
+    value: String,
+    expires_at: Instant,
+    /// Position in the recency order
+    tick: u64,
+}
+
+/// LRU state: entries by key plus keys ordered from least to most recently used.
+#[derive(Default)]
+struct Lru {
+    slots: HashMap<String, Slot>,
+    order: BTreeMap<u64, String>,
+    bytes: usize,
+    next_tick: u64,
+}
+
+impl Lru {
+    fn size(key: &str, value: &str) -> usize {
+        key.len() + value.len()
+    }
+
+    fn touch(&mut self, key: &str) -> Option<&Slot> {
+        let tick = self.next_tick;
+        let slot = self.slots.get_mut(key)?;
+        self.order.remove(&slot.tick);
+        self.order.insert(tick, key.to_string());
+        slot.tick = tick;
+        self.next_tick += 1;
+        Some(slot)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Slot> {
+        let slot = self.slots.remove(key)?;
+        self.order.remove(&slot.tick);
+        self.bytes -= Self::size(key, &slot.value);
+        Some(slot)
+    }
+
+    fn insert(&mut self, key: &str, value: &str, ttl: Duration, max_bytes: usize) {
+        self.remove(key);
+        let size = Self::size(key, value);
+        if size > max_bytes {
+            return;
+        }
+        while self.bytes + size > max_bytes {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(slot) = self.slots.remove(&oldest) {
+                self.bytes -= Self::size(&oldest, &slot.value);
+                metrics::counter!("cache_memory_evictions_total").increment(1);
+            }
+        }
+
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.order.insert(tick, key.to_string());
+        self.slots.insert(
+            key.to_string(),
+            Slot {
+                value: value.to_string(),
+                expires_at: Instant::now() + ttl,
+                tick,
+            },
+        );
+        self.bytes += size;
+    }
+
+    /// Live value of `key`, dropping it if it has expired.
+    fn get(&mut self, key: &str) -> Option<String> {
+        self.get_with_ttl(key).map(|(value, _)| value)
+    }
+
+    /// Live value of `key` and the time left before it expires.
+    fn get_with_ttl(&mut self, key: &str) -> Option<(String, Duration)> {
+        let now = Instant::now();
+        let expired = self.slots.get(key)?.expires_at <= now;
+        if expired {
+            self.remove(key);
+            return None;
+        }
+        self.touch(key)
+            .map(|slot| (slot.value.clone(), slot.expires_at - now))
+    }
+}
+
+/// Byte-bounded in-memory LRU cache with per-entry TTLs.
+pub struct MemoryCacheRepository {
+    max_bytes: usize,
+    lru: Mutex<Lru>,
+}
+
+impl MemoryCacheRepository {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+
+    fn with_lru<R>(&self, f: impl FnOnce(&mut Lru) -> R) -> R {
+        let mut lru = self.lru.lock().unwrap_or_else(PoisonError::into_inner);
+        let result = f(&mut lru);
+        metrics::gauge!("cache_memory_bytes").set(lru.bytes as f64);
+        result
+    }
+}
+
+#[async_trait]
+impl CacheRepository for MemoryCacheRepository {
+    async fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let value = self.with_lru(|lru| lru.get(key));
+        let operation = if value.is_some() { "hit" } else { "miss" };
+        metrics::counter!("cache_tier_operations_total", "tier" => "memory", "operation" => operation)
+            .increment(1);
+        Ok(value)
+    }
+
+    async fn get_with_ttl(&self, key: &str) -> anyhow::Result<Option<(String, Option<u64>)>> {
+        let entry = self.with_lru(|lru| lru.get_with_ttl(key));
+        Ok(entry.map(|(value, ttl)| (value, Some(ttl.as_secs()))))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl_seconds: u64) -> anyhow::Result<()> {
+        let ttl = Duration::from_secs(ttl_seconds);
+        self.with_lru(|lru| lru.insert(key, value, ttl, self.max_bytes));
+        Ok(())
+    }
+
+    async fn try_lock(&self, key: &str, owner: &str, ttl_seconds: u64) -> anyhow::Result<bool> {
+        let ttl = Duration::from_secs(ttl_seconds);
+        Ok(self.with_lru(|lru| {
+            if lru.get(key).is_some() {
+                return false;
+            }
+            lru.insert(key, owner, ttl, self.max_bytes);
+            true
+        }))
+    }
+
+    async fn unlock(&self, key: &str, owner: &str) -> anyhow::Result<()> {
+        self.with_lru(|lru| {
+            if lru.get(key).as_deref() == Some(owner) {
+                lru.remove(key);
+            }
+        });
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<bool> {
+        Ok(self.with_lru(|lru| lru.remove(key).is_some()))
+    }
+
+    async fn scan(&self, pattern: &str, limit: usize) -> anyhow::Result<Vec<String>> {
+        let now = Instant::now();
+        Ok(self.with_lru(|lru| {
+            lru.slots
+                .iter()
+                .filter(|(key, slot)| slot.expires_at > now && glob_match(pattern, key))
+                .map(|(key, _)| key.clone())
+                .take(limit)
+                .collect()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 10-byte value, so each one-letter key takes 11 bytes.
+    const VALUE: &str = "0123456789";
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_by_bytes() {
+        let cache = MemoryCacheRepository::new(33);
+        cache.set("a", VALUE, 60).await.unwrap();
+        cache.set("b", VALUE, 60).await.unwrap();
+        cache.set("c", VALUE, 60).await.unwrap();
+        // Reading `a` makes `b` the least recently used entry
+        assert!(cache.get("a").await.unwrap().is_some());
+
+        cache.set("d", VALUE, 60).await.unwrap();
+        assert_eq!(cache.get("b").await.unwrap(), None);
+        for key in ["a", "c", "d"] {
+            assert_eq!(cache.get(key).await.unwrap().as_deref(), Some(VALUE));
+        }
+
+        // Replacing an entry frees its old size first, so adding `e` only
+        // needs to evict `a`
+        cache.set("d", "x", 60).await.unwrap();
+        cache.set("e", VALUE, 60).await.unwrap();
+        assert_eq!(cache.get("a").await.unwrap(), None);
+        assert!(cache.get("c").await.unwrap().is_some());
+
+        // Entries larger than the whole cache are not stored
+        cache.set("big", &VALUE.repeat(4), 60).await.unwrap();
+        assert_eq!(cache.get("big").await.unwrap(), None);
+        assert!(cache.get("e").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_dropped() {
+        let cache = MemoryCacheRepository::new(1024);
+        cache.set("gone", VALUE, 0).await.unwrap();
+        cache.set("kept", VALUE, 60).await.unwrap();
+
+        assert_eq!(cache.get("gone").await.unwrap(), None);
+        assert!(!cache.delete("gone").await.unwrap());
+        assert_eq!(cache.scan("*", 10).await.unwrap(), vec!["kept".to_string()]);
+
+        let (value, ttl) = cache.get_with_ttl("kept").await.unwrap().unwrap();
+        assert_eq!(value, VALUE);
+        assert!(ttl.is_some_and(|ttl| (59..=60).contains(&ttl)));
+    }
+
+    #[tokio::test]
+    async fn locks_are_held_by_their_owner() {
+        let cache = MemoryCacheRepository::new(1024);
+        assert!(cache.try_lock("lock", "one", 60).await.unwrap());
+        assert!(!cache.try_lock("lock", "two", 60).await.unwrap());
+
+        // Only the owner can release the lock
+        cache.unlock("lock", "two").await.unwrap();
+        assert!(!cache.try_lock("lock", "two", 60).await.unwrap());
+        cache.unlock("lock", "one").await.unwrap();
+        assert!(cache.try_lock("lock", "two", 60).await.unwrap());
+
+        // An expired lock can be taken over
+        assert!(cache.try_lock("short", "one", 0).await.unwrap());
+        assert!(cache.try_lock("short", "two", 60).await.unwrap());
+    }
+}
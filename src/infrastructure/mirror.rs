@@ -0,0 +1,450 @@
+//! Git mirror content backend.
+//!
+//! This module provides `GitMirrorRepository`, a `ContentRepository` that keeps a shallow
+//! local clone of each configured repository and answers requests from the working tree
+//! instead of calling the GitHub contents API once per file.
+//!
+//! # Features
+//!
+//! - Shallow clone (`--depth 1`) per `RepoConfig`, refreshed on a fixed schedule
+//! - On-demand sync when a request finds the mirror stale
+//! - Current commit SHA and last sync time exposed through `/health`
+//! - Commit changes, with the paths they touched, broadcast to subscribers
+//! - Transparent fallback to the wrapped upstream (normally `GitHubRepository`) while the
+//!   mirror is missing or older than `stale_after_secs`
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! mirror:
+//!   enabled: true
+//!   path: "./mirror"
+//!   sync_interval_secs: 300
+//!   stale_after_secs: 1800
+//! ```
+//!
+//! Requires the `git` binary on `PATH`. When `GITHUB_TOKEN` is set it is passed to git as
+//! an HTTP header for each invocation and never written to the clone's configuration.
+
+use crate::domain::{Content, ContentRepository, RepoConfig};
+use crate::infrastructure::filesystem::{FilesystemRepository, FILE_URL_SCHEME};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::{broadcast, Mutex, Notify, RwLock};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+/// Maximum time a single git invocation may take before it is abandoned.
+const GIT_COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Minimum spacing between on-demand syncs, so a failing upstream is not hammered.
+const MIN_ON_DEMAND_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Commit changes buffered for slow subscribers.
+const UPDATE_CHANNEL_CAPACITY: usize = 16;
+
+/// Mirror settings loaded from the `mirror` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MirrorConfig {
+    /// Serve `github` repositories from local clones (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory holding the clones, one `{owner}/{repo}` folder each (default: "./mirror")
+    #[serde(default = "default_mirror_path")]
+    pub path: String,
+    /// Seconds between scheduled pulls (default: 300)
+    #[serde(default = "default_sync_interval_secs")]
+    pub sync_interval_secs: u64,
+    /// Age after which the mirror is considered stale and requests fall back upstream (default: 1800)
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+}
+
+fn default_mirror_path() -> String {
+    "./mirror".to_string()
+}
+fn default_sync_interval_secs() -> u64 {
+    300
+}
+fn default_stale_after_secs() -> u64 {
+    1800
+}
+
+/// Sync state of a single mirrored repository, as reported by `/health`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MirrorStatus {
+    /// Repository in `owner/repo` form
+    pub repo: String,
+    /// Commit SHA currently checked out
+    pub commit: Option<String>,
+    /// Time of the last successful sync (ISO 8601)
+    pub last_sync: Option<String>,
+    /// Whether requests are currently served from the mirror
+    pub fresh: bool,
+    /// Error from the most recent failed sync, if any
+    pub last_error: Option<String>,
+}
+
+/// A mirrored repository moving to a new commit.
+#[derive(Debug, Clone)]
+pub struct MirrorUpdate {
+    /// Repository that changed
+    pub repo: RepoConfig,
+    /// Commit SHA checked out before the sync
+    pub previous: String,
+    /// Commit SHA checked out now
+    pub commit: String,
+    /// Paths changed between the two commits, if git could tell
+    pub changed_paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MirrorState {
+    commit: Option<String>,
+    last_sync: Option<DateTime<Utc>>,
+    last_error: Option<String>,
+}
+
+/// Content repository serving from local git clones with upstream fallback.
+pub struct GitMirrorRepository {
+    settings: MirrorConfig,
+    /// Upstream used while a mirror is missing or stale
+    fallback: Arc<dyn ContentRepository>,
+    /// Reads the working tree of each clone
+    files: FilesystemRepository,
+    /// GitHub token forwarded to git for authenticated fetches
+    token: Option<String>,
+    state: RwLock<HashMap<String, MirrorState>>,
+    /// Serializes git operations per repository
+    sync_locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// Wakes the sync loop for an on-demand pull
+    sync_requested: Notify,
+    /// Publishes commit changes
+    updates: broadcast::Sender<MirrorUpdate>,
+}
+
+impl GitMirrorRepository {
+    /// Create a new mirror repository.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - Mirror settings from `config.yaml`
+    /// * `fallback` - Upstream backend used while a mirror is missing or stale
+    /// * `token` - Optional GitHub token used for authenticated fetches
+    pub fn new(
+        settings: MirrorConfig,
+        fallback: Arc<dyn ContentRepository>,
+        token: Option<String>,
+    ) -> Self {
+        let files = FilesystemRepository::new(settings.path.clone());
+        Self {
+            settings,
+            fallback,
+            files,
+            token,
+            state: RwLock::new(HashMap::new()),
+            sync_locks: Mutex::new(HashMap::new()),
+            sync_requested: Notify::new(),
+            updates: broadcast::channel(UPDATE_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    fn repo_key(config: &RepoConfig) -> String {
+        format!("{}/{}", config.owner, config.repo)
+    }
+
+    fn clone_dir(&self, config: &RepoConfig) -> PathBuf {
+        Path::new(&self.settings.path)
+            .join(&config.owner)
+            .join(&config.repo)
+    }
+
+    /// Configuration pointing the filesystem reader at the clone's working tree.
+    fn local_config(&self, config: &RepoConfig) -> RepoConfig {
+        RepoConfig {
+            root: Some(self.clone_dir(config).to_string_lossy().into_owned()),
+            ..config.clone()
+        }
+    }
+
+    /// Whether the mirror for `config` has synced within `stale_after_secs`.
+    async fn is_fresh(&self, config: &RepoConfig) -> bool {
+        let state = self.state.read().await;
+        state
+            .get(&Self::repo_key(config))
+            .and_then(|s| s.last_sync)
+            .map(|t| {
+                Utc::now().signed_duration_since(t).num_seconds()
+                    < self.settings.stale_after_secs as i64
+            })
+            .unwrap_or(false)
+    }
+
+    /// Ask the background sync loop to pull immediately.
+    pub fn request_sync(&self) {
+        self.sync_requested.notify_one();
+    }
+
+    /// Receive an update whenever a sync moves a repository to a new commit.
+    ///
+    /// The first sync after startup is not reported, since the previous commit
+    /// is unknown.
+    pub fn subscribe(&self) -> broadcast::Receiver<MirrorUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// Current sync state of every mirrored repository.
+    pub async fn status(&self) -> Vec<MirrorStatus> {
+        let state = self.state.read().await;
+        let now = Utc::now();
+        let mut statuses: Vec<MirrorStatus> = state
+            .iter()
+            .map(|(repo, s)| MirrorStatus {
+                repo: repo.clone(),
+                commit: s.commit.clone(),
+                last_sync: s.last_sync.map(|t| t.to_rfc3339()),
+                fresh: s
+                    .last_sync
+                    .map(|t| {
+                        now.signed_duration_since(t).num_seconds()
+                            < self.settings.stale_after_secs as i64
+                    })
+                    .unwrap_or(false),
+                last_error: s.last_error.clone(),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.repo.cmp(&b.repo));
+        statuses
+    }
+
+    /// Clone or update the mirror for `config` and record the resulting commit.
+    ///
+    /// Returns the commit SHA checked out after the sync.
+    pub async fn sync(&self, config: &RepoConfig) -> anyhow::Result<String> {
+        let key = Self::repo_key(config);
+        let lock = {
+            let mut locks = self.sync_locks.lock().await;
+            locks.entry(key.clone()).or_default().clone()
+        };
+        let _guard = lock.lock().await;
+
+        let previous = {
+            let state = self.state.read().await;
+            state.get(&key).and_then(|s| s.commit.clone())
+        };
+        let result = self.sync_locked(config).await;
+
+        let mut update = None;
+        if let (Ok(commit), Some(previous)) = (&result, previous) {
+            if *commit != previous {
+                update = Some(MirrorUpdate {
+                    repo: config.clone(),
+                    changed_paths: self.changed_paths(config, &previous, commit).await,
+                    previous,
+                    commit: commit.clone(),
+                });
+            }
+        }
+
+        let mut state = self.state.write().await;
+        let entry = state.entry(key.clone()).or_default();
+        match &result {
+            Ok(commit) => {
+                if entry.commit.as_deref() != Some(commit.as_str()) {
+                    info!("Mirror {} now at commit {}", key, commit);
+                }
+                entry.commit = Some(commit.clone());
+                entry.last_sync = Some(Utc::now());
+                entry.last_error = None;
+                metrics::counter!("mirror_sync_total", "repo" => key, "result" => "ok")
+                    .increment(1);
+            }
+            Err(e) => {
+                error!("Mirror sync failed for {}: {}", key, e);
+                entry.last_error = Some(e.to_string());
+                metrics::counter!("mirror_sync_total", "repo" => key, "result" => "error")
+                    .increment(1);
+            }
+        }
+        drop(state);
+
+        if let Some(update) = update {
+            // No subscribers is fine
+            let _ = self.updates.send(update);
+        }
+        result
+    }
+
+    /// Paths that differ between two commits of the clone.
+    ///
+    /// Returns `None` when git cannot compare them, e.g. because the previous
+    /// commit is no longer in the shallow clone.
+    async fn changed_paths(
+        &self,
+        config: &RepoConfig,
+        previous: &str,
+        commit: &str,
+    ) -> Option<Vec<String>> {
+        let dir = self.clone_dir(config).to_string_lossy().into_owned();
+        match self
+            .run_git(&["-C", &dir, "diff", "--name-only", previous, commit])
+            .await
+        {
+            Ok(output) => Some(output.lines().map(String::from).collect()),
+            Err(e) => {
+                warn!(
+                    "Could not list paths changed in {}: {}",
+                    Self::repo_key(config),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    async fn sync_locked(&self, config: &RepoConfig) -> anyhow::Result<String> {
+        let dir = self.clone_dir(config);
+        let dir_str = dir.to_string_lossy().into_owned();
+
+        if dir.join(".git").exists() {
+            self.run_git(&["-C", &dir_str, "fetch", "--depth", "1", "origin"])
+                .await?;
+            self.run_git(&["-C", &dir_str, "reset", "--hard", "FETCH_HEAD"])
+                .await?;
+        } else {
+            if let Some(parent) = dir.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let remote = format!("https://github.com/{}/{}.git", config.owner, config.repo);
+            info!("Cloning mirror of {} into {}", remote, dir_str);
+            self.run_git(&[
+                "clone",
+                "--depth",
+                "1",
+                "--single-branch",
+                &remote,
+                &dir_str,
+            ])
+            .await?;
+        }
+
+        let commit = self.run_git(&["-C", &dir_str, "rev-parse", "HEAD"]).await?;
+        Ok(commit.trim().to_string())
+    }
+
+    /// Run a git command, returning its stdout.
+    async fn run_git(&self, args: &[&str]) -> anyhow::Result<String> {
+        let mut cmd = Command::new("git");
+        if let Some(token) = &self.token {
+            let credentials = general_purpose::STANDARD.encode(format!("x-access-token:{}", token));
+            cmd.arg("-c").arg(format!(
+                "http.extraHeader=Authorization: Basic {}",
+                credentials
+            ));
+        }
+        cmd.args(args)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .kill_on_drop(true);
+
+        let output = tokio::time::timeout(GIT_COMMAND_TIMEOUT, cmd.output())
+            .await
+            .map_err(|_| anyhow::anyhow!("git {} timed out", args.join(" ")))??;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Spawn the background task that keeps every mirror up to date.
+    ///
+    /// Syncs immediately, then every `sync_interval_secs` or whenever
+    /// `request_sync` is called.
+    pub fn spawn_sync_loop(self: Arc<Self>, repos: Vec<RepoConfig>) {
+        let interval = Duration::from_secs(self.settings.sync_interval_secs.max(1));
+        tokio::spawn(async move {
+            loop {
+                let started = tokio::time::Instant::now();
+                for config in &repos {
+                    let _ = self.sync(config).await;
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {},
+                    _ = self.sync_requested.notified() => {
+                        info!("On-demand mirror sync requested");
+                        tokio::time::sleep_until(started + MIN_ON_DEMAND_INTERVAL).await;
+                    },
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl ContentRepository for GitMirrorRepository {
+    async fn get_content(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Content> {
+        if self.is_fresh(config).await {
+            return self
+                .files
+                .get_content(&self.local_config(config), path)
+                .await;
+        }
+        warn!(
+            "Mirror stale for {}, falling back upstream",
+            Self::repo_key(config)
+        );
+        self.request_sync();
+        self.fallback.get_content(config, path).await
+    }
+
+    async fn list_directory(
+        &self,
+        config: &RepoConfig,
+        path: &str,
+    ) -> anyhow::Result<Vec<Content>> {
+        if self.is_fresh(config).await {
+            return self
+                .files
+                .list_directory(&self.local_config(config), path)
+                .await;
+        }
+        warn!(
+            "Mirror stale for {}, falling back upstream",
+            Self::repo_key(config)
+        );
+        self.request_sync();
+        self.fallback.list_directory(config, path).await
+    }
+
+    async fn list_tree(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Vec<Content>> {
+        if self.is_fresh(config).await {
+            return self.files.list_tree(&self.local_config(config), path).await;
+        }
+        warn!(
+            "Mirror stale for {}, falling back upstream",
+            Self::repo_key(config)
+        );
+        self.request_sync();
+        self.fallback.list_tree(config, path).await
+    }
+
+    async fn get_raw_file(&self, url: &str) -> anyhow::Result<Value> {
+        if url.starts_with(FILE_URL_SCHEME) {
+            self.files.get_raw_file(url).await
+        } else {
+            self.fallback.get_raw_file(url).await
+        }
+    }
+}
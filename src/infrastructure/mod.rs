@@ -1,5 +1,20 @@
+pub mod breaker;
+pub mod credentials;
+pub mod filesystem;
 pub mod github;
+pub mod memory;
+pub mod mirror;
 pub mod redis;
+pub mod retry;
+pub mod router;
+pub mod tiered;
 
+pub use breaker::{CircuitBreaker, CircuitBreakerRepository};
+pub use credentials::CredentialPool;
+pub use filesystem::FilesystemRepository;
 pub use github::GitHubRepository;
+pub use memory::MemoryCacheRepository;
+pub use mirror::GitMirrorRepository;
 pub use redis::RedisRepository;
+pub use router::SourceRouter;
+pub use tiered::TieredCacheRepository;
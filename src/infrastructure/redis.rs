@@ -4,6 +4,30 @@ use deadpool_redis::redis::AsyncCommands;
 use deadpool_redis::{Config, Pool, Runtime};
 use tracing::{error, info};
 
+/// Deletes `KEYS[1]` only when its value is `ARGV[1]`.
+const UNLOCK_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// Keys requested per `SCAN` round trip.
+const SCAN_BATCH: usize = 1000;
+
+/// Escape a `*`-only key pattern for Redis, whose patterns also treat `?`,
+/// `[`, `]` and `\` as special.
+fn redis_pattern(pattern: &str) -> String {
+    let mut escaped = String::with_capacity(pattern.len());
+    for c in pattern.chars() {
+        if matches!(c, '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 pub struct RedisRepository {
     pool: Option<Pool>,
 }
@@ -26,6 +50,11 @@ impl RedisRepository {
             Self { pool: None }
         }
     }
+
+    /// Whether a Redis connection pool is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.pool.is_some()
+    }
 }
 
 #[async_trait]
@@ -35,6 +64,9 @@ impl CacheRepository for RedisRepository {
             match pool.get().await {
                 Ok(mut conn) => {
                     let result: Option<String> = conn.get(key).await.ok();
+                    let operation = if result.is_some() { "hit" } else { "miss" };
+                    metrics::counter!("cache_tier_operations_total", "tier" => "redis", "operation" => operation)
+                        .increment(1);
                     Ok(result)
                 }
                 Err(e) => {
@@ -47,6 +79,36 @@ impl CacheRepository for RedisRepository {
         }
     }
 
+    async fn get_with_ttl(&self, key: &str) -> anyhow::Result<Option<(String, Option<u64>)>> {
+        if let Some(pool) = &self.pool {
+            match pool.get().await {
+                Ok(mut conn) => {
+                    let result: Option<(Option<String>, i64)> = deadpool_redis::redis::pipe()
+                        .get(key)
+                        .ttl(key)
+                        .query_async(&mut conn)
+                        .await
+                        .ok();
+                    let Some((Some(value), ttl)) = result else {
+                        metrics::counter!("cache_tier_operations_total", "tier" => "redis", "operation" => "miss")
+                            .increment(1);
+                        return Ok(None);
+                    };
+                    metrics::counter!("cache_tier_operations_total", "tier" => "redis", "operation" => "hit")
+                        .increment(1);
+                    // TTL is -1 for keys without an expiry
+                    Ok(Some((value, u64::try_from(ttl).ok())))
+                }
+                Err(e) => {
+                    error!("Failed to get Redis connection from pool: {}", e);
+                    Ok(None)
+                }
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
     async fn set(&self, key: &str, value: &str, ttl_seconds: u64) -> anyhow::Result<()> {
         if let Some(pool) = &self.pool {
             match pool.get().await {
@@ -60,4 +122,97 @@ impl CacheRepository for RedisRepository {
         }
         Ok(())
     }
+
+    async fn try_lock(&self, key: &str, owner: &str, ttl_seconds: u64) -> anyhow::Result<bool> {
+        if let Some(pool) = &self.pool {
+            match pool.get().await {
+                Ok(mut conn) => {
+                    // SET NX replies OK when the key was set and nil when it already exists
+                    let reply: Option<String> = deadpool_redis::redis::cmd("SET")
+                        .arg(key)
+                        .arg(owner)
+                        .arg("NX")
+                        .arg("EX")
+                        .arg(ttl_seconds)
+                        .query_async(&mut conn)
+                        .await?;
+                    Ok(reply.is_some())
+                }
+                Err(e) => {
+                    error!("Failed to get Redis connection from pool: {}", e);
+                    Err(anyhow::anyhow!("Redis unavailable for lock {}: {}", key, e))
+                }
+            }
+        } else {
+            Ok(true)
+        }
+    }
+
+    async fn unlock(&self, key: &str, owner: &str) -> anyhow::Result<()> {
+        if let Some(pool) = &self.pool {
+            match pool.get().await {
+                Ok(mut conn) => {
+                    // Delete only if the lock still holds our owner id
+                    let _: i64 = deadpool_redis::redis::cmd("EVAL")
+                        .arg(UNLOCK_SCRIPT)
+                        .arg(1)
+                        .arg(key)
+                        .arg(owner)
+                        .query_async(&mut conn)
+                        .await?;
+                }
+                Err(e) => {
+                    error!("Failed to get Redis connection from pool: {}", e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<bool> {
+        if let Some(pool) = &self.pool {
+            match pool.get().await {
+                Ok(mut conn) => {
+                    let removed: i64 = conn.del(key).await?;
+                    return Ok(removed > 0);
+                }
+                Err(e) => {
+                    error!("Failed to get Redis connection from pool: {}", e);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    async fn scan(&self, pattern: &str, limit: usize) -> anyhow::Result<Vec<String>> {
+        let mut keys = Vec::new();
+        if let Some(pool) = &self.pool {
+            match pool.get().await {
+                Ok(mut conn) => {
+                    let pattern = redis_pattern(pattern);
+                    let mut cursor: u64 = 0;
+                    loop {
+                        let (next, batch): (u64, Vec<String>) = deadpool_redis::redis::cmd("SCAN")
+                            .arg(cursor)
+                            .arg("MATCH")
+                            .arg(&pattern)
+                            .arg("COUNT")
+                            .arg(SCAN_BATCH)
+                            .query_async(&mut conn)
+                            .await?;
+                        keys.extend(batch);
+                        cursor = next;
+                        if cursor == 0 || keys.len() >= limit {
+                            break;
+                        }
+                    }
+                    keys.truncate(limit);
+                }
+                Err(e) => {
+                    error!("Failed to get Redis connection from pool: {}", e);
+                }
+            }
+        }
+        Ok(keys)
+    }
 }
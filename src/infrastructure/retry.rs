@@ -0,0 +1,181 @@
+//! Retry policy for upstream HTTP requests.
+//!
+//! Failed requests are retried with capped exponential backoff and full
+//! jitter: the wait before retry `n` is uniformly random between zero and
+//! `min(max_delay_ms, base_delay_ms * 2^(n-1))`, which keeps replicas that
+//! failed together from retrying together. Retries stop after `max_attempts`
+//! or when the next wait would end past `deadline_secs` from the first
+//! attempt, so a request never outlives the HTTP timeout of the endpoint that
+//! triggered it.
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! github:
+//!   retry:
+//!     max_attempts: 4
+//!     base_delay_ms: 250
+//!     max_delay_ms: 8000
+//!     retryable_statuses: [429, 500, 502, 503, 504]
+//!     deadline_secs: 20
+//! ```
+
+use crate::domain::random_up_to;
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Retry settings loaded from the `github.retry` section of `config.yaml`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct RetryConfig {
+    /// Attempts per request, including the first (default: 4)
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Backoff before the first retry, before jitter, in milliseconds (default: 250)
+    #[serde(default = "default_base_delay_ms")]
+    pub base_delay_ms: u64,
+    /// Longest backoff between attempts, in milliseconds (default: 8000)
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Response statuses that are retried (default: 429, 500, 502, 503, 504).
+    /// A 403 is only retried when it reports a rate limit.
+    #[serde(default = "default_retryable_statuses")]
+    pub retryable_statuses: Vec<u16>,
+    /// Time, in seconds from the first attempt, after which no retry is started (default: 20)
+    #[serde(default = "default_deadline_secs")]
+    pub deadline_secs: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    4
+}
+fn default_base_delay_ms() -> u64 {
+    250
+}
+fn default_max_delay_ms() -> u64 {
+    8000
+}
+fn default_retryable_statuses() -> Vec<u16> {
+    vec![429, 500, 502, 503, 504]
+}
+fn default_deadline_secs() -> u64 {
+    20
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            base_delay_ms: default_base_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+            retryable_statuses: default_retryable_statuses(),
+            deadline_secs: default_deadline_secs(),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Whether responses with `status` are retried.
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// Overall time budget of a request, retries included.
+    pub fn deadline(&self) -> Duration {
+        Duration::from_secs(self.deadline_secs)
+    }
+
+    /// Jittered wait before retry number `retry` (starting at 1).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponential = self
+            .base_delay_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(32));
+        let cap = exponential.min(self.max_delay_ms);
+        Duration::from_millis(random_up_to(cap))
+    }
+}
+
+/// Wait requested by a `Retry-After` header given in seconds.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(base_delay_ms: u64, max_delay_ms: u64) -> RetryConfig {
+        RetryConfig {
+            base_delay_ms,
+            max_delay_ms,
+            ..RetryConfig::default()
+        }
+    }
+
+    /// Longest backoff drawn over enough samples to approach the bound.
+    fn longest_backoff(config: &RetryConfig, retry: u32) -> Duration {
+        (0..200).map(|_| config.backoff(retry)).max().unwrap()
+    }
+
+    #[test]
+    fn backoff_doubles_within_jitter_bound() {
+        let config = config(100, 10_000);
+        for (retry, bound) in [(0, 100), (1, 100), (2, 200), (3, 400), (5, 1600)] {
+            let longest = longest_backoff(&config, retry);
+            assert!(longest <= Duration::from_millis(bound), "retry {}", retry);
+            assert!(
+                longest > Duration::from_millis(bound / 2),
+                "retry {}",
+                retry
+            );
+        }
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let longest = longest_backoff(&config(250, 8000), 10);
+        assert!(longest <= Duration::from_millis(8000));
+        assert!(longest > Duration::from_millis(4000));
+
+        assert_eq!(longest_backoff(&config(250, 0), 3), Duration::ZERO);
+    }
+
+    #[test]
+    fn backoff_saturates() {
+        // The exponential overflows, so the cap applies
+        let longest = longest_backoff(&config(u64::MAX, 8000), 2);
+        assert!(longest <= Duration::from_millis(8000));
+        assert!(longest > Duration::from_millis(4000));
+
+        // The exponent stops growing after 32 doublings
+        let uncapped = config(1000, u64::MAX);
+        for retry in [33, 64, u32::MAX] {
+            assert!(uncapped.backoff(retry) <= Duration::from_millis(1000 << 32));
+        }
+    }
+
+    #[test]
+    fn retry_after_reads_seconds() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert("retry-after", " 7 ".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            "retry-after",
+            "Wed, 21 Oct 2026 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        let config = RetryConfig::default();
+        assert!(config.retries_status(503));
+        assert!(!config.retries_status(404));
+        assert!(!config.retries_status(403));
+    }
+}
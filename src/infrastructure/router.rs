@@ -0,0 +1,208 @@
+//! Source-based dispatch between content backends.
+//!
+//! Each entry in `allowed_repos` names a `source` (e.g. `github`, `local`). The
+//! `SourceRouter` holds one `ContentRepository` per source and forwards every call to
+//! the backend registered for the requested repository, so the application services
+//! stay unaware of where content actually comes from.
+//!
+//! Raw file URLs carry no repository, so they are routed by their location instead:
+//! `file://` URLs go to the backend that registered their root directory (e.g. the git
+//! mirror for its clones) or to the `local` backend, and API URLs go to `github`.
+
+use crate::domain::{Content, ContentRepository, RepoConfig};
+use crate::infrastructure::filesystem::FILE_URL_SCHEME;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Source name served by the filesystem backend.
+pub const LOCAL_SOURCE: &str = "local";
+/// Source name served by the GitHub backend.
+pub const GITHUB_SOURCE: &str = "github";
+
+/// Content repository that dispatches to a backend per `RepoConfig::source`.
+#[derive(Default)]
+pub struct SourceRouter {
+    backends: HashMap<String, Arc<dyn ContentRepository>>,
+    /// Directories whose `file://` URLs are served by a source other than `local`
+    file_roots: Vec<(PathBuf, String)>,
+}
+
+impl SourceRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the backend serving repositories with the given `source`.
+    pub fn with_source(
+        mut self,
+        source: impl Into<String>,
+        backend: Arc<dyn ContentRepository>,
+    ) -> Self {
+        self.backends.insert(source.into(), backend);
+        self
+    }
+
+    /// Route `file://` URLs below `root` to the backend serving `source`.
+    ///
+    /// Needed for backends that hand out URLs of their own files on disk, such as
+    /// the git mirror, whose clones live outside the `local` backend's roots.
+    pub fn with_file_root(mut self, source: impl Into<String>, root: impl Into<PathBuf>) -> Self {
+        self.file_roots.push((root.into(), source.into()));
+        self
+    }
+
+    fn backend(&self, source: &str) -> anyhow::Result<&Arc<dyn ContentRepository>> {
+        self.backends
+            .get(source)
+            .ok_or_else(|| anyhow::anyhow!("No content backend configured for source: {}", source))
+    }
+
+    /// Pick the backend for a raw file URL.
+    ///
+    /// `file://` URLs go to the source whose registered root contains the file,
+    /// and to the `local` backend otherwise; everything else is an API URL
+    /// produced by the GitHub backend.
+    fn backend_for_url(&self, url: &str) -> anyhow::Result<&Arc<dyn ContentRepository>> {
+        let Some(path) = url.strip_prefix(FILE_URL_SCHEME) else {
+            return self.backend(GITHUB_SOURCE);
+        };
+        // File URLs hold canonical paths, so compare against the canonical roots
+        let path = Path::new(path);
+        let owner = self
+            .file_roots
+            .iter()
+            .find(|(root, _)| std::fs::canonicalize(root).is_ok_and(|root| path.starts_with(root)));
+        self.backend(owner.map_or(LOCAL_SOURCE, |(_, source)| source.as_str()))
+    }
+}
+
+#[async_trait]
+impl ContentRepository for SourceRouter {
+    async fn get_content(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Content> {
+        self.backend(&config.source)?
+            .get_content(config, path)
+            .await
+    }
+
+    async fn list_directory(
+        &self,
+        config: &RepoConfig,
+        path: &str,
+    ) -> anyhow::Result<Vec<Content>> {
+        self.backend(&config.source)?
+            .list_directory(config, path)
+            .await
+    }
+
+    async fn list_tree(&self, config: &RepoConfig, path: &str) -> anyhow::Result<Vec<Content>> {
+        self.backend(&config.source)?.list_tree(config, path).await
+    }
+
+    async fn get_raw_file(&self, url: &str) -> anyhow::Result<Value> {
+        self.backend_for_url(url)?.get_raw_file(url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::mirror::MirrorConfig;
+    use crate::infrastructure::{FilesystemRepository, GitMirrorRepository};
+    use std::process::Command;
+
+    fn git(args: &[&str]) {
+        let output = Command::new("git").args(args).output().unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// An upstream git repository with one data file, and an empty local data root.
+    fn fixture(base: &Path) -> (PathBuf, PathBuf) {
+        let upstream = base.join("upstream");
+        let local = base.join("local");
+        std::fs::create_dir_all(upstream.join("data/kaspa")).unwrap();
+        std::fs::create_dir_all(local.join("data")).unwrap();
+        std::fs::write(
+            upstream.join("data/kaspa/2025-12-29.json"),
+            r#"{"close": 0.05}"#,
+        )
+        .unwrap();
+        let dir = upstream.to_string_lossy().into_owned();
+        git(&["init", "-q", &dir]);
+        git(&["-C", &dir, "add", "."]);
+        git(&[
+            "-C",
+            &dir,
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "data",
+        ]);
+        (upstream, local)
+    }
+
+    #[tokio::test]
+    async fn mirrored_files_are_read_through_the_mirror() {
+        let base = std::env::temp_dir().join(format!("gatewayapi-router-{}", uuid::Uuid::new_v4()));
+        let (upstream, local) = fixture(&base);
+        let mirror_path = base.join("mirror");
+        let config = RepoConfig {
+            source: GITHUB_SOURCE.to_string(),
+            owner: "KaspaDev".to_string(),
+            repo: "Kaspa-Exchange-Data".to_string(),
+            root: None,
+        };
+
+        // A clone whose origin is the upstream fixture, synced like the real mirror
+        let clone = mirror_path.join(&config.owner).join(&config.repo);
+        git(&[
+            "clone",
+            "-q",
+            &format!("file://{}", upstream.display()),
+            &clone.to_string_lossy(),
+        ]);
+        let local_repo = Arc::new(FilesystemRepository::new(&local));
+        let mirror = Arc::new(GitMirrorRepository::new(
+            MirrorConfig {
+                enabled: true,
+                path: mirror_path.to_string_lossy().into_owned(),
+                sync_interval_secs: 300,
+                stale_after_secs: 1800,
+            },
+            local_repo.clone(),
+            None,
+        ));
+        mirror.sync(&config).await.unwrap();
+
+        let router = SourceRouter::new()
+            .with_source(GITHUB_SOURCE, mirror.clone())
+            .with_source(LOCAL_SOURCE, local_repo.clone())
+            .with_file_root(GITHUB_SOURCE, &mirror_path);
+        let listing = router.list_directory(&config, "data/kaspa").await.unwrap();
+        assert_eq!(listing.len(), 1);
+        let url = &listing[0].url;
+        assert!(url.starts_with(FILE_URL_SCHEME));
+        let value = router.get_raw_file(url).await.unwrap();
+        assert_eq!(value["close"], 0.05);
+
+        // Without the mirror's root the URL would reach the local backend, which refuses it
+        let unrouted = SourceRouter::new()
+            .with_source(GITHUB_SOURCE, mirror)
+            .with_source(LOCAL_SOURCE, local_repo);
+        let err = unrouted.get_raw_file(url).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid path"));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
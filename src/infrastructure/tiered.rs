@@ -0,0 +1,136 @@
+//! Layered cache: an in-process tier in front of a shared one.
+//!
+//! Reads try the memory tier first and fall back to the shared tier (Redis),
+//! copying hits into memory. Writes go to both. Memory copies are kept for at
+//! most `max_ttl_secs`, so values changed through another replica are seen
+//! within that time, and never outlive the shared entry they were copied from. Locks are always taken in the shared tier, since they
+//! coordinate replicas, and keys are listed from the shared tier, which holds
+//! every entry.
+
+use crate::domain::CacheRepository;
+use crate::infrastructure::memory::MemoryCacheRepository;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Cache repository combining a memory tier with a shared tier.
+pub struct TieredCacheRepository {
+    memory: Arc<MemoryCacheRepository>,
+    shared: Arc<dyn CacheRepository>,
+    /// Longest TTL, in seconds, of a memory copy
+    max_memory_ttl: u64,
+}
+
+impl TieredCacheRepository {
+    pub fn new(
+        memory: Arc<MemoryCacheRepository>,
+        shared: Arc<dyn CacheRepository>,
+        max_memory_ttl: u64,
+    ) -> Self {
+        Self {
+            memory,
+            shared,
+            max_memory_ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl CacheRepository for TieredCacheRepository {
+    async fn get(&self, key: &str) -> anyhow::Result<Option<String>> {
+        if let Some(value) = self.memory.get(key).await? {
+            return Ok(Some(value));
+        }
+        let Some((value, remaining)) = self.shared.get_with_ttl(key).await? else {
+            return Ok(None);
+        };
+        let ttl = remaining.map_or(self.max_memory_ttl, |r| r.min(self.max_memory_ttl));
+        if ttl > 0 {
+            self.memory.set(key, &value, ttl).await?;
+        }
+        Ok(Some(value))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl_seconds: u64) -> anyhow::Result<()> {
+        self.memory
+            .set(key, value, ttl_seconds.min(self.max_memory_ttl))
+            .await?;
+        self.shared.set(key, value, ttl_seconds).await
+    }
+
+    async fn try_lock(&self, key: &str, owner: &str, ttl_seconds: u64) -> anyhow::Result<bool> {
+        self.shared.try_lock(key, owner, ttl_seconds).await
+    }
+
+    async fn unlock(&self, key: &str, owner: &str) -> anyhow::Result<()> {
+        self.shared.unlock(key, owner).await
+    }
+
+    async fn delete(&self, key: &str) -> anyhow::Result<bool> {
+        let in_memory = self.memory.delete(key).await?;
+        Ok(self.shared.delete(key).await? || in_memory)
+    }
+
+    async fn scan(&self, pattern: &str, limit: usize) -> anyhow::Result<Vec<String>> {
+        self.shared.scan(pattern, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiers() -> (
+        Arc<MemoryCacheRepository>,
+        Arc<MemoryCacheRepository>,
+        TieredCacheRepository,
+    ) {
+        let memory = Arc::new(MemoryCacheRepository::new(1 << 20));
+        let shared = Arc::new(MemoryCacheRepository::new(1 << 20));
+        let tiered = TieredCacheRepository::new(memory.clone(), shared.clone(), 60);
+        (memory, shared, tiered)
+    }
+
+    async fn memory_ttl(memory: &MemoryCacheRepository, key: &str) -> u64 {
+        let (_, ttl) = memory.get_with_ttl(key).await.unwrap().unwrap();
+        ttl.unwrap()
+    }
+
+    #[tokio::test]
+    async fn shared_hits_are_copied_for_their_remaining_ttl() {
+        let (memory, shared, tiered) = tiers();
+        shared.set("short", "value", 5).await.unwrap();
+        shared.set("long", "value", 3600).await.unwrap();
+
+        assert_eq!(tiered.get("short").await.unwrap().as_deref(), Some("value"));
+        assert_eq!(tiered.get("long").await.unwrap().as_deref(), Some("value"));
+        assert!(memory_ttl(&memory, "short").await <= 5);
+        assert!((59..=60).contains(&memory_ttl(&memory, "long").await));
+
+        assert_eq!(tiered.get("missing").await.unwrap(), None);
+        assert_eq!(memory.get("missing").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn writes_and_deletes_reach_both_tiers() {
+        let (memory, shared, tiered) = tiers();
+        tiered.set("key", "value", 3600).await.unwrap();
+        assert!((59..=60).contains(&memory_ttl(&memory, "key").await));
+        assert!(memory_ttl(&shared, "key").await > 60);
+
+        assert!(tiered.delete("key").await.unwrap());
+        assert_eq!(memory.get("key").await.unwrap(), None);
+        assert_eq!(shared.get("key").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn locks_are_taken_in_the_shared_tier() {
+        let (memory, shared, tiered) = tiers();
+        assert!(tiered.try_lock("lock", "one", 60).await.unwrap());
+        assert_eq!(memory.get("lock").await.unwrap(), None);
+        assert!(!shared.try_lock("lock", "two", 60).await.unwrap());
+
+        tiered.unlock("lock", "one").await.unwrap();
+        assert!(shared.try_lock("lock", "two", 60).await.unwrap());
+        assert!(!tiered.try_lock("lock", "one", 60).await.unwrap());
+    }
+}
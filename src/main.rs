@@ -14,6 +14,7 @@
 //! # Features
 //!
 //! - ✅ GitHub API integration with rate limit handling and exponential backoff
+//! - ✅ Circuit breaker that fails fast while GitHub is unavailable
 //! - ✅ Redis caching with connection pooling for performance
 //! - ✅ Prometheus metrics for observability
 //! - ✅ Request correlation IDs for distributed tracing
@@ -27,7 +28,13 @@
 //! - `GITHUB_TOKEN`: GitHub personal access token (optional)
 //!   - If set: Uses authenticated requests (5,000 req/hour limit)
 //!   - If not set: Uses unauthenticated requests (60 req/hour limit for public repos)
+//! - `GITHUB_TOKENS`: Additional comma-separated tokens; requests go to the one with the most budget left
+//! - `GITHUB_APP_ID`, `GITHUB_APP_INSTALLATION_ID`, `GITHUB_APP_PRIVATE_KEY_PATH`: GitHub App
+//!   installation used as an additional credential (optional)
 //! - `REDIS_URL`: Redis connection string (default: redis://localhost:6379)
+//! - `DATA_ROOT`: Default root for `source: local` repositories (default: current directory)
+//! - `ADMIN_TOKEN`: Bearer token for the admin endpoints (optional; admin is disabled without it)
+//! - `GITHUB_WEBHOOK_SECRET`: Secret GitHub webhook deliveries are signed with (optional)
 //! - `RUST_LOG`: Logging level (default: info)
 //!
 //! # Quick Start
@@ -53,14 +60,35 @@ mod infrastructure;
 
 use crate::api::routes::create_router;
 use crate::api::state::AppState;
-use crate::application::{ContentService, TickerService};
+use crate::application::cache::CacheConfig;
+use crate::application::cache_warmer::WarmerConfig;
+use crate::application::catalog_index::CatalogConfig;
+use crate::application::index_service::IndexConfig;
+use crate::application::quote_service::QuoteConfig;
+use crate::application::ticker_service::TickerConfig;
+use crate::application::{
+    CacheAdminService, CacheWarmer, CatalogIndex, CatalogService, ContentService, IndexService,
+    QuoteService, ResponseCache, TickerService, WebhookService,
+};
 use crate::domain::RepoConfig;
-use crate::infrastructure::{GitHubRepository, RedisRepository};
+use crate::domain::{CacheRepository, ContentRepository};
+use crate::infrastructure::breaker::CircuitBreakerConfig;
+use crate::infrastructure::credentials::GitHubAppConfig;
+use crate::infrastructure::github::GitHubConfig;
+use crate::infrastructure::memory::MemoryCacheConfig;
+use crate::infrastructure::mirror::{MirrorConfig, MirrorUpdate};
+use crate::infrastructure::router::{GITHUB_SOURCE, LOCAL_SOURCE};
+use crate::infrastructure::{
+    CircuitBreaker, CircuitBreakerRepository, CredentialPool, FilesystemRepository,
+    GitHubRepository, GitMirrorRepository, MemoryCacheRepository, RedisRepository, SourceRouter,
+    TieredCacheRepository,
+};
 use anyhow::Context;
 use serde::Deserialize;
 use std::env;
 use std::fs;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 /// Top-level application configuration loaded from `config.yaml`.
@@ -72,6 +100,36 @@ struct Config {
     server: ServerConfig,
     /// List of allowed repositories that can be accessed through the API
     allowed_repos: Vec<RepoConfig>,
+    /// GitHub API client settings
+    #[serde(default)]
+    github: GitHubConfig,
+    /// Fast failure while GitHub is unavailable
+    #[serde(default)]
+    circuit_breaker: CircuitBreakerConfig,
+    /// Optional local git mirror for `github` repositories
+    #[serde(default)]
+    mirror: Option<MirrorConfig>,
+    /// Stale/outlier flagging for the ticker stats endpoint
+    #[serde(default)]
+    ticker: TickerConfig,
+    /// Quote-currency normalization for the ticker endpoints
+    #[serde(default)]
+    quotes: QuoteConfig,
+    /// Composite price index settings
+    #[serde(default)]
+    index: IndexConfig,
+    /// Background index of the data tree
+    #[serde(default)]
+    catalog: CatalogConfig,
+    /// Response cache soft/hard TTLs
+    #[serde(default)]
+    cache: CacheConfig,
+    /// In-process cache tier in front of Redis
+    #[serde(default)]
+    memory_cache: MemoryCacheConfig,
+    /// Proactive refresh of hot ticker cache entries
+    #[serde(default)]
+    warmer: WarmerConfig,
 }
 
 /// Server configuration settings.
@@ -104,13 +162,6 @@ fn default_allowed_origins() -> String {
 async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
-    let github_token = env::var("GITHUB_TOKEN").ok();
-    if github_token.is_none() {
-        tracing::warn!("GITHUB_TOKEN not found in env - using unauthenticated requests (60 req/hour limit for public repos). For higher limits (5,000 req/hour), set GITHUB_TOKEN in .env");
-    } else {
-        tracing::info!("GITHUB_TOKEN found - using authenticated requests (5,000 req/hour limit)");
-    }
-
     tracing_subscriber::registry()
         .with(EnvFilter::new(
             std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
@@ -118,17 +169,135 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // Record metrics from startup on; `/metrics` renders them
+    let metrics = metrics_exporter_prometheus::PrometheusBuilder::new()
+        .install_recorder()
+        .context("Failed to install Prometheus recorder")?;
+
     // Load Config
     let config_content = fs::read_to_string("config.yaml")
         .context("Failed to read config.yaml - ensure file exists in working directory")?;
     let config: Config = serde_yaml::from_str(&config_content)
         .context("Failed to parse config.yaml - check YAML syntax and structure")?;
 
+    let github_tokens: Vec<String> = env::var("GITHUB_TOKEN")
+        .into_iter()
+        .chain(env::var("GITHUB_TOKENS"))
+        .flat_map(|v| {
+            v.split(',')
+                .map(|t| t.trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|t| !t.is_empty())
+        .collect();
+    let github_token = github_tokens.first().cloned();
+    let mut credentials = CredentialPool::from_tokens(github_tokens);
+    if let (Ok(app_id), Ok(installation_id), Ok(key_path)) = (
+        env::var("GITHUB_APP_ID"),
+        env::var("GITHUB_APP_INSTALLATION_ID"),
+        env::var("GITHUB_APP_PRIVATE_KEY_PATH"),
+    ) {
+        let private_key_pem = fs::read_to_string(&key_path)
+            .with_context(|| format!("Failed to read GitHub App private key {}", key_path))?;
+        credentials = credentials.with_app(GitHubAppConfig {
+            app_id,
+            installation_id,
+            private_key_pem,
+        })?;
+    }
+    match credentials.authenticated_count() {
+        0 => tracing::warn!("GITHUB_TOKEN not found in env - using unauthenticated requests (60 req/hour limit for public repos). For higher limits (5,000 req/hour), set GITHUB_TOKEN in .env"),
+        n => tracing::info!("{} GitHub credential(s) configured - using authenticated requests (5,000 req/hour limit each)", n),
+    }
+
     let redis_url = env::var("REDIS_URL").ok();
 
+    let admin_token = env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+    if admin_token.is_none() {
+        tracing::info!("ADMIN_TOKEN not set - admin endpoints disabled");
+    }
+    let webhook_secret = env::var("GITHUB_WEBHOOK_SECRET")
+        .ok()
+        .filter(|s| !s.is_empty());
+    if webhook_secret.is_none() {
+        tracing::info!("GITHUB_WEBHOOK_SECRET not set - GitHub webhooks disabled");
+    }
+
+    let data_root = env::var("DATA_ROOT").unwrap_or_else(|_| ".".to_string());
+
     // Infrastructure
-    let github_repo = Arc::new(GitHubRepository::new(github_token));
     let redis_repo = Arc::new(RedisRepository::new(redis_url));
+    let memory_cache = &config.memory_cache;
+    let cache_repo: Arc<dyn CacheRepository> = if !memory_cache.enabled {
+        redis_repo
+    } else if redis_repo.is_enabled() {
+        Arc::new(TieredCacheRepository::new(
+            Arc::new(MemoryCacheRepository::new(memory_cache.max_bytes)),
+            redis_repo,
+            memory_cache.max_ttl_secs,
+        ))
+    } else {
+        tracing::info!("Using in-memory cache only");
+        Arc::new(MemoryCacheRepository::new(memory_cache.max_bytes))
+    };
+
+    let mut github_repo = GitHubRepository::new(None)
+        .with_credentials(credentials)
+        .with_max_file_bytes(config.github.max_file_bytes)
+        .with_retry(config.github.retry.clone());
+    if config.github.conditional_requests {
+        github_repo = github_repo.with_etag_cache(cache_repo.clone(), config.github.etag_ttl_secs);
+    }
+    // Fail fast instead of waiting through retries while GitHub keeps failing
+    let circuit_breaker = config
+        .circuit_breaker
+        .enabled
+        .then(|| Arc::new(CircuitBreaker::new(config.circuit_breaker.clone())));
+    let github_repo: Arc<dyn ContentRepository> = match &circuit_breaker {
+        Some(breaker) => Arc::new(CircuitBreakerRepository::new(
+            GITHUB_SOURCE,
+            Arc::new(github_repo),
+            breaker.clone(),
+        )),
+        None => Arc::new(github_repo),
+    };
+    let local_roots = config
+        .allowed_repos
+        .iter()
+        .filter(|r| r.source == LOCAL_SOURCE)
+        .filter_map(|r| r.root.clone());
+    let filesystem_repo = Arc::new(FilesystemRepository::new(data_root).with_roots(local_roots));
+
+    // Serve GitHub repositories from a local clone when the mirror is enabled
+    let mirror = config.mirror.clone().filter(|m| m.enabled).map(|settings| {
+        Arc::new(GitMirrorRepository::new(
+            settings,
+            github_repo.clone(),
+            github_token,
+        ))
+    });
+    let github_backend: Arc<dyn ContentRepository> = match &mirror {
+        Some(mirror) => {
+            let mirrored: Vec<RepoConfig> = config
+                .allowed_repos
+                .iter()
+                .filter(|r| r.source == GITHUB_SOURCE)
+                .cloned()
+                .collect();
+            mirror.clone().spawn_sync_loop(mirrored);
+            mirror.clone()
+        }
+        None => github_repo,
+    };
+
+    let mut router = SourceRouter::new()
+        .with_source(GITHUB_SOURCE, github_backend)
+        .with_source(LOCAL_SOURCE, filesystem_repo);
+    if let Some(settings) = config.mirror.as_ref().filter(|m| m.enabled) {
+        // Files listed from the clones are read back through the mirror
+        router = router.with_file_root(GITHUB_SOURCE, &settings.path);
+    }
+    let content_repo = Arc::new(router);
 
     // Get default repo for ticker service (first allowed repo)
     let default_repo = config
@@ -138,21 +307,88 @@ async fn main() -> anyhow::Result<()> {
         .expect("At least one allowed repo must be configured");
 
     // Application
+    let response_cache = ResponseCache::new(cache_repo.clone(), config.cache.clone());
+
+    let catalog = Arc::new(CatalogIndex::new(
+        content_repo.clone(),
+        cache_repo.clone(),
+        default_repo.clone(),
+        config.catalog.clone(),
+    ));
+    catalog.clone().spawn_refresh_loop();
+
     let content_service = Arc::new(ContentService::new(
-        github_repo.clone(),
-        redis_repo.clone(),
+        content_repo.clone(),
+        cache_repo.clone(),
+        response_cache.clone(),
         config.allowed_repos.clone(),
+        catalog.clone(),
+    ));
+
+    let quote_service = Arc::new(QuoteService::new(
+        content_repo.clone(),
+        default_repo.clone(),
+        config.quotes.clone(),
+        catalog.clone(),
+        response_cache.clone(),
     ));
 
     let ticker_service = Arc::new(TickerService::new(
-        github_repo,
-        redis_repo,
+        content_repo.clone(),
+        response_cache.clone(),
+        default_repo.clone(),
+        config.ticker.clone(),
+        quote_service.clone(),
+        catalog.clone(),
+    ));
+
+    Arc::new(CacheWarmer::new(
+        ticker_service.clone(),
+        quote_service.clone(),
+        cache_repo.clone(),
+        config.warmer.clone(),
+    ))
+    .spawn();
+
+    let index_service = Arc::new(IndexService::new(
+        content_repo.clone(),
+        response_cache.clone(),
+        default_repo.clone(),
+        config.index.clone(),
+        catalog.clone(),
+        quote_service.clone(),
+    ));
+
+    let catalog_service = Arc::new(CatalogService::new(
+        content_repo,
+        cache_repo.clone(),
         default_repo,
+        catalog.clone(),
+    ));
+
+    let cache_admin = Arc::new(CacheAdminService::new(cache_repo));
+    if let Some(mirror) = &mirror {
+        spawn_mirror_invalidation(mirror.subscribe(), cache_admin.clone(), catalog.clone());
+    }
+    let webhook_service = Arc::new(WebhookService::new(
+        cache_admin.clone(),
+        catalog.clone(),
+        config.allowed_repos.clone(),
     ));
 
     let state = AppState {
         content_service,
         ticker_service,
+        index_service,
+        quote_service,
+        catalog_service,
+        cache_admin,
+        webhook_service,
+        metrics,
+        mirror,
+        circuit_breaker,
+        admin_token,
+        webhook_secret,
     };
 
     let app = create_router(state, config.server.allowed_origins.clone());
@@ -173,6 +409,46 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Invalidate the cache entries affected, and refresh the catalog index,
+/// whenever the mirror syncs a new commit.
+fn spawn_mirror_invalidation(
+    mut updates: broadcast::Receiver<MirrorUpdate>,
+    cache_admin: Arc<CacheAdminService>,
+    catalog: Arc<CatalogIndex>,
+) {
+    tokio::spawn(async move {
+        loop {
+            match updates.recv().await {
+                Ok(update) => {
+                    tracing::info!(
+                        "Mirror moved {}/{} from {} to {}, invalidating cache",
+                        update.repo.owner,
+                        update.repo.repo,
+                        update.previous,
+                        update.commit
+                    );
+                    if let Err(e) = cache_admin
+                        .invalidate_changes(&update.repo, update.changed_paths.as_deref())
+                        .await
+                    {
+                        tracing::error!("Cache invalidation failed: {}", e);
+                    }
+                    if let Err(e) = catalog
+                        .refresh_changes(&update.repo, update.changed_paths.as_deref())
+                        .await
+                    {
+                        tracing::warn!("Catalog refresh after mirror update failed: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::warn!("Missed {} mirror updates", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
 /// Wait for SIGTERM or SIGINT (Ctrl+C) to initiate graceful shutdown
 async fn shutdown_signal() {
     let ctrl_c = async {